@@ -0,0 +1,108 @@
+//! alias names target platforms in the style of Go's GOOS and GOARCH.
+//!
+//! Aliases accept either rustc metadata or the fields of a parsed target triple.
+
+use crate::{Target, TargetMetadata};
+
+/// DEFAULT_ALIAS_PRECEDENCE denotes the default target patterns, from most to least preferred,
+/// for choosing between targets sharing a platform alias.
+///
+/// Beyond preferring the conventional libc, 386 favors i686 over i586,
+/// and arm favors hard float armv7 over older arm revisions.
+pub static DEFAULT_ALIAS_PRECEDENCE: [&str; 8] = [
+    "i686-*-msvc",
+    "*-msvc",
+    "i686-*-gnu",
+    "*-gnu",
+    "armv7-*-gnueabihf",
+    "i686-*",
+    "armv7-*-*eabihf",
+    "*",
+];
+
+/// triple_metadata approximates target metadata from the fields of a target triple,
+/// for targets unknown to rustc.
+pub fn triple_metadata(target: &Target) -> TargetMetadata {
+    TargetMetadata {
+        arch: target.arch.clone(),
+        vendor: target.vendor.clone(),
+        os: match target.os.as_deref() {
+            Some("darwin") => "macos".to_string(),
+            os => os.unwrap_or_default().to_string(),
+        },
+        abi: target.abi.clone().unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// arch_alias names the chipset of a target, e.g. "amd64" for x86_64.
+pub fn arch_alias(metadata: &TargetMetadata) -> String {
+    let little_endian: bool = metadata.endian == "little";
+
+    match metadata.arch.as_str() {
+        "x86_64" => "amd64".to_string(),
+        "x86" | "i386" | "i586" | "i686" => "386".to_string(),
+        "aarch64" | "arm64ec" => "arm64".to_string(),
+        "powerpc64le" => "ppc64le".to_string(),
+        "mipsel" => "mipsle".to_string(),
+        "mips64el" => "mips64le".to_string(),
+        "riscv64gc" => "riscv64".to_string(),
+        "sparcv9" => "sparc64".to_string(),
+        arch if arch.starts_with("armv") || arch.starts_with("thumbv") => "arm".to_string(),
+        "loongarch64" => "loong64".to_string(),
+        "powerpc" => "ppc".to_string(),
+        "powerpc64" if little_endian => "ppc64le".to_string(),
+        "powerpc64" => "ppc64".to_string(),
+        "mips" if little_endian => "mipsle".to_string(),
+        "mips64" if little_endian => "mips64le".to_string(),
+        "wasm32" | "wasm64" => "wasm".to_string(),
+        arch => arch.to_string(),
+    }
+}
+
+/// os_alias names the operating system of a target, e.g. "darwin" for macOS.
+pub fn os_alias(metadata: &TargetMetadata) -> String {
+    match metadata.os.as_str() {
+        "macos" => "darwin".to_string(),
+        "wasi" => "wasip1".to_string(),
+        "emscripten" => "js".to_string(),
+        "" | "none" => "none".to_string(),
+        os => os.to_string(),
+    }
+}
+
+#[test]
+fn test_aliases() {
+    let metadata = |arch: &str, os: &str, endian: &str| TargetMetadata {
+        arch: arch.to_string(),
+        os: os.to_string(),
+        endian: endian.to_string(),
+        ..Default::default()
+    };
+
+    let linux_amd64 = metadata("x86_64", "linux", "little");
+    assert_eq!(arch_alias(&linux_amd64), "amd64");
+    assert_eq!(os_alias(&linux_amd64), "linux");
+
+    let darwin_arm64 = metadata("aarch64", "macos", "little");
+    assert_eq!(arch_alias(&darwin_arm64), "arm64");
+    assert_eq!(os_alias(&darwin_arm64), "darwin");
+
+    assert_eq!(arch_alias(&metadata("x86", "windows", "little")), "386");
+    assert_eq!(arch_alias(&metadata("powerpc64", "linux", "big")), "ppc64");
+    assert_eq!(
+        arch_alias(&metadata("powerpc64", "linux", "little")),
+        "ppc64le"
+    );
+    assert_eq!(
+        arch_alias(&metadata("riscv64", "linux", "little")),
+        "riscv64"
+    );
+    assert_eq!(os_alias(&metadata("thumbv7em", "none", "little")), "none");
+
+    // Triple spellings
+    assert_eq!(arch_alias(&metadata("i686", "windows", "")), "386");
+    assert_eq!(arch_alias(&metadata("armv7", "linux", "")), "arm");
+    assert_eq!(arch_alias(&metadata("powerpc64le", "linux", "")), "ppc64le");
+    assert_eq!(os_alias(&metadata("thumbv7em", "", "")), "none");
+}
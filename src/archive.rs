@@ -0,0 +1,376 @@
+//! archive packages collated artifacts for distribution.
+
+use crate::CritError;
+
+use std::collections;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path;
+use std::time;
+
+/// ArchiveFormat models supported archive file formats.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArchiveFormat {
+    /// TarGz denotes gzip compressed tarballs.
+    TarGz,
+
+    /// TarXz denotes xz compressed tarballs.
+    TarXz,
+
+    /// Zip denotes deflate compressed zip files.
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// parse reads an archive format by file extension.
+    pub fn parse(s: &str) -> Result<ArchiveFormat, CritError> {
+        match s {
+            "tar.gz" => Ok(ArchiveFormat::TarGz),
+            "tar.xz" => Ok(ArchiveFormat::TarXz),
+            "zip" => Ok(ArchiveFormat::Zip),
+            _ => Err(CritError::IOError(format!(
+                "unsupported archive format: {s} (expected tar.gz, tar.xz, or zip)"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArchiveFormat::TarGz => write!(f, "tar.gz"),
+            ArchiveFormat::TarXz => write!(f, "tar.xz"),
+            ArchiveFormat::Zip => write!(f, "zip"),
+        }
+    }
+}
+
+/// Entry models a file or directory within an archive.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// path denotes the slash separated location within the archive.
+    pub path: String,
+
+    /// source denotes the file to read, or none for directories.
+    pub source: Option<path::PathBuf>,
+
+    /// mode denotes UNIX permission bits.
+    pub mode: u32,
+
+    /// mtime denotes the modification time, in seconds since the UNIX epoch.
+    pub mtime: u64,
+}
+
+impl Entry {
+    /// directory generates a directory entry.
+    fn directory(archive_path: &str, mtime: u64) -> Entry {
+        Entry {
+            path: format!("{archive_path}/"),
+            source: None,
+            mode: 0o755,
+            mtime,
+        }
+    }
+
+    /// file generates a file entry.
+    fn file(archive_path: &str, source_path: &path::Path) -> Result<Entry, CritError> {
+        Ok(Entry {
+            path: archive_path.to_string(),
+            source: Some(source_path.to_path_buf()),
+            mode: file_mode(source_path)?,
+            mtime: modification_time(source_path)?,
+        })
+    }
+}
+
+/// modification_time reads file modification times.
+fn modification_time(pth: &path::Path) -> Result<u64, CritError> {
+    let modified: time::SystemTime = fs::metadata(pth)
+        .and_then(|e| e.modified())
+        .map_err(|err| CritError::IOError(format!("unable to stat {}: {err}", pth.display())))?;
+
+    Ok(modified
+        .duration_since(time::UNIX_EPOCH)
+        .map(|e| e.as_secs())
+        .unwrap_or(0))
+}
+
+/// file_mode reads UNIX permission bits.
+#[cfg(unix)]
+fn file_mode(pth: &path::Path) -> Result<u32, CritError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(pth)
+        .map(|e| e.permissions().mode() & 0o777)
+        .map_err(|err| CritError::IOError(format!("unable to stat {}: {err}", pth.display())))
+}
+
+/// file_mode assumes executable permission bits on hosts lacking UNIX permissions.
+#[cfg(not(unix))]
+fn file_mode(_: &path::Path) -> Result<u32, CritError> {
+    Ok(0o755)
+}
+
+/// collect_entries lists files, given as slash separated archive paths and source files,
+/// nested under a root directory name, followed by any extra files placed directly in the root directory.
+pub fn collect_entries(
+    root_name: &str,
+    files: &[(String, path::PathBuf)],
+    extra_files: &[path::PathBuf],
+) -> Result<Vec<Entry>, CritError> {
+    let mut file_entries: Vec<Entry> = Vec::new();
+
+    for (archive_path, source_path) in files {
+        file_entries.push(Entry::file(
+            &format!("{root_name}/{archive_path}"),
+            source_path,
+        )?);
+    }
+
+    for extra_file in extra_files {
+        let file_name: String = extra_file
+            .file_name()
+            .map(|e| e.to_string_lossy().to_string())
+            .ok_or(CritError::PathRenderError(format!(
+                "invalid archive file: {}",
+                extra_file.display()
+            )))?;
+
+        if !extra_file.is_file() {
+            return Err(CritError::IOError(format!(
+                "missing archive file: {}",
+                extra_file.display()
+            )));
+        }
+
+        file_entries.push(Entry::file(
+            &format!("{root_name}/{file_name}"),
+            extra_file,
+        )?);
+    }
+
+    let mtime: u64 = file_entries.iter().map(|e| e.mtime).max().unwrap_or(0);
+    let mut dir_paths: collections::BTreeSet<String> = collections::BTreeSet::new();
+
+    for entry in &file_entries {
+        let mut dir_path: &str = &entry.path;
+
+        while let Some((parent, _)) = dir_path.rsplit_once('/') {
+            dir_paths.insert(parent.to_string());
+            dir_path = parent;
+        }
+    }
+
+    let mut entries: Vec<Entry> = dir_paths
+        .iter()
+        .map(|e| Entry::directory(e, mtime))
+        .collect();
+    entries.extend(file_entries);
+    Ok(entries)
+}
+
+/// write_archive writes entries to an archive file, removing any partial archive on failure.
+pub fn write_archive(
+    format: ArchiveFormat,
+    archive_path: &path::Path,
+    entries: &[Entry],
+) -> Result<(), CritError> {
+    let archive_file: fs::File = fs::File::create(archive_path).map_err(|err| {
+        CritError::IOError(format!(
+            "unable to create archive {}: {err}",
+            archive_path.display()
+        ))
+    })?;
+
+    let result: Result<(), CritError> = match format {
+        ArchiveFormat::TarGz => write_tar(
+            flate2::write::GzEncoder::new(
+                io::BufWriter::new(archive_file),
+                flate2::Compression::best(),
+            ),
+            entries,
+        )
+        .and_then(|e| {
+            e.finish()
+                .and_then(|mut e| e.flush())
+                .map_err(|err| CritError::IOError(err.to_string()))
+        }),
+        ArchiveFormat::TarXz => write_tar(
+            liblzma::write::XzEncoder::new(io::BufWriter::new(archive_file), 9),
+            entries,
+        )
+        .and_then(|e| {
+            e.finish()
+                .and_then(|mut e| e.flush())
+                .map_err(|err| CritError::IOError(err.to_string()))
+        }),
+        ArchiveFormat::Zip => write_zip(io::BufWriter::new(archive_file), entries),
+    };
+
+    if result.is_err() {
+        let _ = fs::remove_file(archive_path);
+    }
+
+    result.map_err(|err| match err {
+        CritError::IOError(message) => {
+            CritError::IOError(format!("{}: {message}", archive_path.display()))
+        }
+        err => err,
+    })
+}
+
+/// write_tar encodes entries as a tar stream.
+fn write_tar<W: Write>(writer: W, entries: &[Entry]) -> Result<W, CritError> {
+    let mut builder: tar::Builder<W> = tar::Builder::new(writer);
+
+    for entry in entries {
+        let mut header: tar::Header = tar::Header::new_gnu();
+        header.set_mode(entry.mode);
+        header.set_mtime(entry.mtime);
+        header.set_uid(0);
+        header.set_gid(0);
+
+        let result: io::Result<()> = match &entry.source {
+            Some(source) => {
+                let source_file: fs::File = fs::File::open(source).map_err(|err| {
+                    CritError::IOError(format!("unable to read {}: {err}", source.display()))
+                })?;
+                let size: u64 = source_file
+                    .metadata()
+                    .map_err(|err| {
+                        CritError::IOError(format!("unable to stat {}: {err}", source.display()))
+                    })?
+                    .len();
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_size(size);
+                builder.append_data(&mut header, &entry.path, source_file)
+            }
+            None => {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_size(0);
+                builder.append_data(&mut header, &entry.path, io::empty())
+            }
+        };
+
+        result.map_err(|err| CritError::IOError(format!("{}: {err}", entry.path)))?;
+    }
+
+    builder
+        .into_inner()
+        .map_err(|err| CritError::IOError(err.to_string()))
+}
+
+/// zip_options configures zip entries with deflate compression, UNIX permission bits, and modification times.
+fn zip_options(entry: &Entry, size: u64) -> zip::write::SimpleFileOptions {
+    let options: zip::write::SimpleFileOptions = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .compression_level(Some(9))
+        .unix_permissions(entry.mode)
+        .large_file(size >= u32::MAX as u64);
+
+    match ::time::OffsetDateTime::from_unix_timestamp(entry.mtime as i64)
+        .ok()
+        .and_then(|e| {
+            zip::DateTime::try_from(::time::PrimitiveDateTime::new(e.date(), e.time())).ok()
+        }) {
+        Some(mtime) => options.last_modified_time(mtime),
+        None => options,
+    }
+}
+
+/// write_zip encodes entries as a zip file.
+fn write_zip<W: Write + io::Seek>(writer: W, entries: &[Entry]) -> Result<(), CritError> {
+    let mut zip_writer: zip::ZipWriter<W> = zip::ZipWriter::new(writer);
+
+    for entry in entries {
+        match &entry.source {
+            Some(source) => {
+                let mut source_file: fs::File = fs::File::open(source).map_err(|err| {
+                    CritError::IOError(format!("unable to read {}: {err}", source.display()))
+                })?;
+                let size: u64 = source_file
+                    .metadata()
+                    .map_err(|err| {
+                        CritError::IOError(format!("unable to stat {}: {err}", source.display()))
+                    })?
+                    .len();
+                zip_writer
+                    .start_file(&entry.path, zip_options(entry, size))
+                    .map_err(|err| CritError::IOError(format!("{}: {err}", entry.path)))?;
+                io::copy(&mut source_file, &mut zip_writer)
+                    .map_err(|err| CritError::IOError(format!("{}: {err}", entry.path)))?;
+            }
+            None => zip_writer
+                .add_directory(entry.path.trim_end_matches('/'), zip_options(entry, 0))
+                .map_err(|err| CritError::IOError(format!("{}: {err}", entry.path)))?,
+        }
+    }
+
+    zip_writer
+        .finish()
+        .and_then(|mut e| e.flush().map_err(zip::result::ZipError::from))
+        .map_err(|err| CritError::IOError(err.to_string()))
+}
+
+#[test]
+fn test_write_archive() -> Result<(), CritError> {
+    let scratch: path::PathBuf =
+        std::env::temp_dir().join(format!("crit-test-write-archive-{}", std::process::id()));
+    fs::create_dir_all(&scratch).map_err(|err| CritError::IOError(err.to_string()))?;
+    let source_path: path::PathBuf = scratch.join("hello");
+    fs::write(&source_path, b"hello").map_err(|err| CritError::IOError(err.to_string()))?;
+
+    let entries: Vec<Entry> = collect_entries(
+        "hello-0.0.1",
+        &[("bin/hello".to_string(), source_path.clone())],
+        &[],
+    )?;
+
+    for format in [ArchiveFormat::TarGz, ArchiveFormat::TarXz] {
+        let archive_path: path::PathBuf = scratch.join(format!("hello.{format}"));
+        write_archive(format, &archive_path, &entries)?;
+
+        let archive_file: fs::File =
+            fs::File::open(&archive_path).map_err(|err| CritError::IOError(err.to_string()))?;
+        let decoder: Box<dyn io::Read> = match format {
+            ArchiveFormat::TarGz => Box::new(flate2::read::GzDecoder::new(archive_file)),
+            _ => Box::new(liblzma::read::XzDecoder::new(archive_file)),
+        };
+        let paths: Vec<String> = tar::Archive::new(decoder)
+            .entries()
+            .and_then(|e| {
+                e.map(|e| e.and_then(|e| Ok(e.path()?.to_string_lossy().to_string())))
+                    .collect()
+            })
+            .map_err(|err| CritError::IOError(err.to_string()))?;
+        assert_eq!(
+            paths,
+            vec!["hello-0.0.1/", "hello-0.0.1/bin/", "hello-0.0.1/bin/hello"]
+        );
+    }
+
+    let zip_path: path::PathBuf = scratch.join("hello.zip");
+    write_archive(ArchiveFormat::Zip, &zip_path, &entries)?;
+    let zip_file: fs::File =
+        fs::File::open(&zip_path).map_err(|err| CritError::IOError(err.to_string()))?;
+    let zip_archive: zip::ZipArchive<fs::File> =
+        zip::ZipArchive::new(zip_file).map_err(|err| CritError::IOError(err.to_string()))?;
+    assert_eq!(
+        zip_archive
+            .file_names()
+            .collect::<collections::BTreeSet<&str>>(),
+        collections::BTreeSet::from(["hello-0.0.1/", "hello-0.0.1/bin/", "hello-0.0.1/bin/hello"])
+    );
+
+    let missing_path: path::PathBuf = scratch.join("missing.tar.xz");
+    let missing_entries: Vec<Entry> = vec![Entry {
+        source: Some(scratch.join("missing")),
+        ..entries[2].clone()
+    }];
+    assert!(write_archive(ArchiveFormat::TarXz, &missing_path, &missing_entries).is_err());
+    assert!(!missing_path.exists());
+
+    fs::remove_dir_all(&scratch).map_err(|err| CritError::IOError(err.to_string()))
+}
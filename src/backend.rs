@@ -0,0 +1,306 @@
+//! backend abstracts the tools that compile each target.
+
+use crate::CritError;
+
+use std::process;
+use std::sync;
+
+/// DEFAULT_BACKEND denotes the backend for targets without a matching backend entry.
+pub static DEFAULT_BACKEND: &str = "cross";
+
+/// RUSTUP_LOCK serializes rustup toolchain changes across concurrent target builds.
+static RUSTUP_LOCK: sync::Mutex<()> = sync::Mutex::new(());
+
+/// BackendOptions customizes backends per target.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BackendOptions {
+    /// glibc_version requests a minimum glibc version for linux-gnu targets (zigbuild only).
+    pub glibc_version: Option<String>,
+}
+
+/// Backend models a build tool.
+pub trait Backend: Send + Sync {
+    /// name identifies the backend in configuration files.
+    fn name(&self) -> &str;
+
+    /// prepare readies the host to build a target.
+    fn prepare(&self, target: &str, debug: bool) -> Result<(), CritError>;
+
+    /// command generates a build command, given cargo build arguments.
+    fn command(&self, target: &str, args: &[String]) -> process::Command;
+}
+
+/// Cross builds targets in cross Docker containers.
+pub struct Cross;
+
+impl Backend for Cross {
+    fn name(&self) -> &str {
+        "cross"
+    }
+
+    fn prepare(&self, _: &str, _: bool) -> Result<(), CritError> {
+        Ok(())
+    }
+
+    fn command(&self, _: &str, args: &[String]) -> process::Command {
+        let mut cmd = process::Command::new("cross");
+        cmd.args(args);
+        cmd
+    }
+}
+
+/// Cargo builds targets natively on the host, installing rustup targets as needed.
+pub struct Cargo;
+
+impl Backend for Cargo {
+    fn name(&self) -> &str {
+        "cargo"
+    }
+
+    fn prepare(&self, target: &str, debug: bool) -> Result<(), CritError> {
+        install_rustup_target(target, debug)
+    }
+
+    fn command(&self, _: &str, args: &[String]) -> process::Command {
+        let mut cmd = process::Command::new("cargo");
+        cmd.args(args);
+        cmd
+    }
+}
+
+/// Zigbuild builds targets natively on the host with cargo-zigbuild, linking with zig.
+pub struct Zigbuild {
+    /// glibc_version denotes an optional glibc version suffix for linux-gnu targets (e.g. "2.17").
+    pub glibc_version: Option<String>,
+}
+
+impl Backend for Zigbuild {
+    fn name(&self) -> &str {
+        "zigbuild"
+    }
+
+    fn prepare(&self, target: &str, debug: bool) -> Result<(), CritError> {
+        install_rustup_target(target, debug)
+    }
+
+    fn command(&self, target: &str, args: &[String]) -> process::Command {
+        let zig_target: String = match &self.glibc_version {
+            Some(glibc_version) if target.contains("-linux-gnu") => {
+                format!("{target}.{glibc_version}")
+            }
+            _ => target.to_string(),
+        };
+
+        let mut cmd = process::Command::new("cargo");
+        cmd.args(translate_args(args, "zigbuild", &zig_target));
+        cmd
+    }
+}
+
+/// Xwin builds MSVC targets natively on the host with cargo-xwin, using the Windows SDK.
+pub struct Xwin;
+
+impl Backend for Xwin {
+    fn name(&self) -> &str {
+        "xwin"
+    }
+
+    fn prepare(&self, target: &str, debug: bool) -> Result<(), CritError> {
+        install_rustup_target(target, debug)
+    }
+
+    fn command(&self, target: &str, args: &[String]) -> process::Command {
+        let mut cmd = process::Command::new("cargo");
+        cmd.arg("xwin");
+        cmd.args(translate_args(args, "build", target));
+        cmd
+    }
+}
+
+/// translate_args rewrites cargo build arguments for cargo subcommand plugins,
+/// replacing the leading `build` subcommand and the `--target` value.
+fn translate_args(args: &[String], subcommand: &str, target: &str) -> Vec<String> {
+    let mut translated: Vec<String> = Vec::new();
+    let mut args_iter = args.iter();
+
+    if let Some(e) = args_iter.next() {
+        translated.push(if e == "build" {
+            subcommand.to_string()
+        } else {
+            e.clone()
+        });
+    }
+
+    while let Some(e) = args_iter.next() {
+        translated.push(e.clone());
+
+        if e == "--target" && args_iter.next().is_some() {
+            translated.push(target.to_string());
+        }
+    }
+
+    translated
+}
+
+#[test]
+fn test_zigbuild_args() {
+    let args: Vec<String> = ["build", "--target", "x86_64-unknown-linux-gnu", "-r"]
+        .iter()
+        .map(|e| e.to_string())
+        .collect();
+
+    let zigbuild = Zigbuild {
+        glibc_version: Some("2.17".to_string()),
+    };
+    let cmd = zigbuild.command("x86_64-unknown-linux-gnu", &args);
+    assert_eq!(
+        cmd.get_args().collect::<Vec<_>>(),
+        [
+            "zigbuild",
+            "--target",
+            "x86_64-unknown-linux-gnu.2.17",
+            "-r"
+        ]
+    );
+
+    let cmd_musl = zigbuild.command("x86_64-unknown-linux-musl", &args);
+    assert_eq!(
+        cmd_musl.get_args().collect::<Vec<_>>(),
+        ["zigbuild", "--target", "x86_64-unknown-linux-musl", "-r"]
+    );
+
+    let cmd_xwin = Xwin.command("x86_64-pc-windows-msvc", &args);
+    assert_eq!(
+        cmd_xwin.get_args().collect::<Vec<_>>(),
+        ["xwin", "build", "--target", "x86_64-pc-windows-msvc", "-r"]
+    );
+}
+
+#[test]
+fn test_lookup() -> Result<(), CritError> {
+    let options = BackendOptions::default();
+
+    for name in ["cross", "cargo", "zigbuild", "xwin"] {
+        assert_eq!(lookup(name, &options)?.name(), name);
+    }
+
+    assert!(lookup("docker", &options).is_err());
+
+    let args: Vec<String> = [
+        "build",
+        "--target",
+        "aarch64-unknown-linux-gnu",
+        "--profile",
+        "release",
+    ]
+    .iter()
+    .map(|e| e.to_string())
+    .collect();
+    let cmd = lookup("cargo", &options)?.command("aarch64-unknown-linux-gnu", &args);
+    assert_eq!(cmd.get_program(), "cargo");
+    assert_eq!(
+        cmd.get_args().collect::<Vec<_>>(),
+        [
+            "build",
+            "--target",
+            "aarch64-unknown-linux-gnu",
+            "--profile",
+            "release"
+        ]
+    );
+
+    let cmd_cross = lookup(DEFAULT_BACKEND, &options)?.command("aarch64-unknown-linux-gnu", &args);
+    assert_eq!(cmd_cross.get_program(), "cross");
+    assert_eq!(
+        cmd_cross.get_args().collect::<Vec<_>>(),
+        [
+            "build",
+            "--target",
+            "aarch64-unknown-linux-gnu",
+            "--profile",
+            "release"
+        ]
+    );
+
+    let zigbuild_options = BackendOptions {
+        glibc_version: Some("2.17".to_string()),
+    };
+    let cmd_zigbuild =
+        lookup("zigbuild", &zigbuild_options)?.command("aarch64-unknown-linux-gnu", &args);
+    assert_eq!(
+        cmd_zigbuild.get_args().collect::<Vec<_>>(),
+        [
+            "zigbuild",
+            "--target",
+            "aarch64-unknown-linux-gnu.2.17",
+            "--profile",
+            "release"
+        ]
+    );
+    Ok(())
+}
+
+/// install_rustup_target adds a Rust target to the active toolchain, when missing.
+pub fn install_rustup_target(target: &str, debug: bool) -> Result<(), CritError> {
+    let _rustup_guard = RUSTUP_LOCK
+        .lock()
+        .map_err(|err| CritError::IOError(format!("unable to lock rustup: {err}")))?;
+
+    let mut cmd_list = process::Command::new("rustup");
+    cmd_list.args(["target", "list", "--installed"]);
+
+    if debug {
+        eprintln!("debug: running command: {:?}", cmd_list);
+    }
+
+    let list_output: process::Output = cmd_list
+        .output()
+        .map_err(|err| CritError::IOError(format!("unable to run rustup: {err}")))?;
+
+    if !list_output.status.success() {
+        return Err(CritError::IOError(format!(
+            "failed to query installed rustup targets: {}",
+            String::from_utf8_lossy(&list_output.stderr)
+        )));
+    }
+
+    if String::from_utf8_lossy(&list_output.stdout)
+        .lines()
+        .any(|e| e.trim() == target)
+    {
+        return Ok(());
+    }
+
+    let mut cmd_add = process::Command::new("rustup");
+    cmd_add.args(["target", "add", target]);
+
+    if debug {
+        eprintln!("debug: running command: {:?}", cmd_add);
+    }
+
+    let add_output: process::Output = cmd_add
+        .output()
+        .map_err(|err| CritError::IOError(format!("unable to run rustup: {err}")))?;
+
+    if !add_output.status.success() {
+        return Err(CritError::IOError(format!(
+            "failed to install rustup target {target}: {}",
+            String::from_utf8_lossy(&add_output.stderr)
+        )));
+    }
+
+    Ok(())
+}
+
+/// lookup resolves backends by name.
+pub fn lookup(name: &str, options: &BackendOptions) -> Result<Box<dyn Backend>, CritError> {
+    match name {
+        "cross" => Ok(Box::new(Cross)),
+        "cargo" => Ok(Box::new(Cargo)),
+        "zigbuild" => Ok(Box::new(Zigbuild {
+            glibc_version: options.glibc_version.clone(),
+        })),
+        "xwin" => Ok(Box::new(Xwin)),
+        _ => Err(CritError::IOError(format!("unsupported backend: {name}"))),
+    }
+}
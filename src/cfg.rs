@@ -0,0 +1,272 @@
+//! cfg evaluates Rust `cfg()` predicates against target metadata.
+
+use crate::CritError;
+
+use std::fmt;
+
+/// Predicate models a Rust configuration predicate.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Predicate {
+    /// Name matches a bare option, e.g. `unix`.
+    Name(String),
+
+    /// KeyValue matches a key-value option, e.g. `target_os = "linux"`.
+    KeyValue(String, String),
+
+    /// All matches when every child predicate matches.
+    All(Vec<Predicate>),
+
+    /// Any matches when at least one child predicate matches.
+    Any(Vec<Predicate>),
+
+    /// Not matches when the child predicate does not match.
+    Not(Box<Predicate>),
+}
+
+/// Token models lexical elements of cfg expressions.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    LeftParen,
+    RightParen,
+    Comma,
+    Equals,
+}
+
+/// tokenize splits cfg expressions into tokens.
+fn tokenize(s: &str) -> Result<Vec<Token>, CritError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => tokens.push(Token::LeftParen),
+            ')' => tokens.push(Token::RightParen),
+            ',' => tokens.push(Token::Comma),
+            '=' => tokens.push(Token::Equals),
+            '"' => {
+                let mut value = String::new();
+
+                loop {
+                    match chars.next() {
+                        None => {
+                            return Err(CritError::CfgParseError(format!(
+                                "unterminated string in cfg expression: {s}"
+                            )));
+                        }
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => value.push(escaped),
+                            None => {
+                                return Err(CritError::CfgParseError(format!(
+                                    "unterminated string in cfg expression: {s}"
+                                )));
+                            }
+                        },
+                        Some(e) => value.push(e),
+                    }
+                }
+
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_whitespace() => (),
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut ident = String::from(c);
+
+                while let Some(&e) = chars.peek() {
+                    if !(e.is_ascii_alphanumeric() || e == '_') {
+                        break;
+                    }
+
+                    ident.push(e);
+                    chars.next();
+                }
+
+                tokens.push(Token::Ident(ident));
+            }
+            c => {
+                return Err(CritError::CfgParseError(format!(
+                    "unexpected character {c:?} in cfg expression: {s}"
+                )));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parser reads predicates from tokens.
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> CritError {
+        CritError::CfgParseError(format!("{message} in cfg expression: {}", self.source))
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), CritError> {
+        match self.next() {
+            Some(e) if e == expected => Ok(()),
+            _ => Err(self.error(&format!("expected {expected:?}"))),
+        }
+    }
+
+    fn predicate(&mut self) -> Result<Predicate, CritError> {
+        let name = match self.next() {
+            Some(Token::Ident(e)) => e,
+            _ => return Err(self.error("expected identifier")),
+        };
+
+        match (name.as_str(), self.peek()) {
+            ("all" | "any" | "not", Some(Token::LeftParen)) => {
+                self.next();
+                let mut children: Vec<Predicate> = Vec::new();
+
+                while self.peek() != Some(&Token::RightParen) {
+                    children.push(self.predicate()?);
+
+                    if self.peek() == Some(&Token::Comma) {
+                        self.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                self.expect(Token::RightParen)?;
+
+                match name.as_str() {
+                    "all" => Ok(Predicate::All(children)),
+                    "any" => Ok(Predicate::Any(children)),
+                    _ => {
+                        if children.len() != 1 {
+                            return Err(self.error("not() requires exactly one predicate"));
+                        }
+
+                        Ok(Predicate::Not(Box::new(children.remove(0))))
+                    }
+                }
+            }
+            (_, Some(Token::Equals)) => {
+                self.next();
+
+                match self.next() {
+                    Some(Token::Str(value)) => Ok(Predicate::KeyValue(name, value)),
+                    _ => Err(self.error("expected string value")),
+                }
+            }
+            _ => Ok(Predicate::Name(name)),
+        }
+    }
+}
+
+impl Predicate {
+    /// parse converts `cfg(...)` expressions to predicates.
+    pub fn parse(s: &str) -> Result<Predicate, CritError> {
+        let mut parser = Parser {
+            source: s,
+            tokens: tokenize(s)?,
+            position: 0,
+        };
+
+        if parser.next() != Some(Token::Ident("cfg".to_string())) {
+            return Err(parser.error("expected cfg(...)"));
+        }
+
+        parser.expect(Token::LeftParen)?;
+        let predicate = parser.predicate()?;
+        parser.expect(Token::RightParen)?;
+
+        if parser.peek().is_some() {
+            return Err(parser.error("unexpected trailing input"));
+        }
+
+        Ok(predicate)
+    }
+
+    /// matches evaluates the predicate against configuration options,
+    /// as reported by `rustc --print cfg`.
+    pub fn matches(&self, cfg: &[(String, Option<String>)]) -> bool {
+        match self {
+            Predicate::Name(name) => cfg.iter().any(|(k, v)| k == name && v.is_none()),
+            Predicate::KeyValue(key, value) => cfg
+                .iter()
+                .any(|(k, v)| k == key && v.as_deref() == Some(value.as_str())),
+            Predicate::All(children) => children.iter().all(|e| e.matches(cfg)),
+            Predicate::Any(children) => children.iter().any(|e| e.matches(cfg)),
+            Predicate::Not(child) => !child.matches(cfg),
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let join = |children: &[Predicate]| {
+            children
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        match self {
+            Predicate::Name(name) => write!(f, "{name}"),
+            Predicate::KeyValue(key, value) => write!(f, "{key} = {value:?}"),
+            Predicate::All(children) => write!(f, "all({})", join(children)),
+            Predicate::Any(children) => write!(f, "any({})", join(children)),
+            Predicate::Not(child) => write!(f, "not({child})"),
+        }
+    }
+}
+
+#[test]
+fn test_predicate_evaluation() -> Result<(), CritError> {
+    let linux_gnu: Vec<(String, Option<String>)> = vec![
+        ("unix".to_string(), None),
+        ("target_os".to_string(), Some("linux".to_string())),
+        ("target_env".to_string(), Some("gnu".to_string())),
+        ("target_pointer_width".to_string(), Some("64".to_string())),
+    ];
+
+    let predicate =
+        Predicate::parse("cfg(all(target_os = \"linux\", target_pointer_width = \"64\"))")?;
+    assert_eq!(
+        predicate,
+        Predicate::All(vec![
+            Predicate::KeyValue("target_os".to_string(), "linux".to_string()),
+            Predicate::KeyValue("target_pointer_width".to_string(), "64".to_string()),
+        ])
+    );
+    assert!(predicate.matches(&linux_gnu));
+    assert!(Predicate::parse("cfg(unix)")?.matches(&linux_gnu));
+    assert!(!Predicate::parse("cfg(windows)")?.matches(&linux_gnu));
+    assert!(!Predicate::parse("cfg(not(target_env = \"gnu\"))")?.matches(&linux_gnu));
+    assert!(
+        Predicate::parse("cfg(any(target_env = \"sgx\", target_os = \"linux\",))")?
+            .matches(&linux_gnu)
+    );
+    assert!(!Predicate::parse("cfg(any())")?.matches(&linux_gnu));
+    assert!(Predicate::parse("cfg(all())")?.matches(&linux_gnu));
+    assert!(Predicate::parse("target_os = \"linux\"").is_err());
+    assert!(Predicate::parse("cfg(target_os = linux)").is_err());
+    assert!(Predicate::parse("cfg(not(unix, windows))").is_err());
+    assert!(Predicate::parse("cfg(unix) extra").is_err());
+    assert_eq!(
+        Predicate::parse("cfg(not(target_env = \"sgx\"))")?.to_string(),
+        "not(target_env = \"sgx\")"
+    );
+    Ok(())
+}
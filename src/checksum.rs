@@ -0,0 +1,169 @@
+//! checksum records and verifies SHA-256 digests of build outputs.
+
+use crate::CritError;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use std::fs;
+use std::io;
+use std::path;
+
+/// SHA256SUMS_FILENAME denotes the coreutils compatible checksum file name.
+pub static SHA256SUMS_FILENAME: &str = "SHA256SUMS";
+
+/// SHA256SUMS_JSON_FILENAME denotes the JSON checksum manifest file name.
+pub static SHA256SUMS_JSON_FILENAME: &str = "SHA256SUMS.json";
+
+/// sha256_file computes the SHA-256 digest of a file.
+pub fn sha256_file(pth: &path::Path) -> Result<String, CritError> {
+    let mut file: io::BufReader<fs::File> = fs::File::open(pth)
+        .map(io::BufReader::new)
+        .map_err(|err| CritError::IOError(format!("unable to read {}: {err}", pth.display())))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)
+        .map_err(|err| CritError::IOError(format!("unable to read {}: {err}", pth.display())))?;
+    Ok(render_digest(&hasher.finalize()))
+}
+
+/// render_digest formats a digest as lowercase hexadecimal.
+fn render_digest(digest: &[u8]) -> String {
+    digest.iter().map(|e| format!("{e:02x}")).collect()
+}
+
+#[test]
+fn test_sha256() {
+    let digest = |data: &[u8]| render_digest(&Sha256::digest(data));
+
+    assert_eq!(
+        digest(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        digest(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        digest(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    assert_eq!(
+        digest(&[b'a'; 1000]),
+        "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3"
+    );
+}
+
+/// Checksum models the digest of a file, relative to the checksum manifest directory.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Checksum {
+    /// path denotes the slash separated file location.
+    pub path: String,
+
+    /// sha256 denotes the lowercase hexadecimal SHA-256 digest.
+    pub sha256: String,
+}
+
+/// ChecksumManifest models the JSON checksum manifest.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ChecksumManifest {
+    /// files collects file digests.
+    pub files: Vec<Checksum>,
+}
+
+/// relative_path renders a path relative to a base directory, with slash separators.
+pub fn relative_path(base_path: &path::Path, pth: &path::Path) -> Result<String, CritError> {
+    let relative: &path::Path = pth.strip_prefix(base_path).map_err(|_| {
+        CritError::PathRenderError(format!(
+            "{} is not within {}",
+            pth.display(),
+            base_path.display()
+        ))
+    })?;
+
+    Ok(relative
+        .components()
+        .map(|e| e.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<String>>()
+        .join("/"))
+}
+
+/// render_sha256sums formats checksums in the coreutils `sha256sum` format.
+pub fn render_sha256sums(checksums: &[Checksum]) -> String {
+    checksums
+        .iter()
+        .map(|e| format!("{}  {}\n", e.sha256, e.path))
+        .collect()
+}
+
+/// write_checksums records the digests of files within a directory,
+/// as both SHA256SUMS and SHA256SUMS.json.
+pub fn write_checksums(
+    dir_path: &path::Path,
+    file_paths: &[path::PathBuf],
+) -> Result<Vec<Checksum>, CritError> {
+    let mut checksums: Vec<Checksum> = Vec::new();
+
+    for file_path in file_paths {
+        checksums.push(Checksum {
+            path: relative_path(dir_path, file_path)?,
+            sha256: sha256_file(file_path)?,
+        });
+    }
+
+    checksums.sort_by(|a, b| a.path.cmp(&b.path));
+    checksums.dedup();
+
+    let manifest = ChecksumManifest {
+        files: checksums.clone(),
+    };
+    let manifest_json: String = serde_json::to_string_pretty(&manifest)
+        .map_err(|err| CritError::IOError(err.to_string()))?;
+
+    fs::write(
+        dir_path.join(SHA256SUMS_FILENAME),
+        render_sha256sums(&checksums),
+    )
+    .and_then(|_| {
+        fs::write(
+            dir_path.join(SHA256SUMS_JSON_FILENAME),
+            format!("{manifest_json}\n"),
+        )
+    })
+    .map_err(|err| CritError::IOError(format!("unable to write checksums: {err}")))?;
+
+    Ok(checksums)
+}
+
+/// verify checks the files of a directory against its JSON checksum manifest,
+/// returning a description of each missing or modified file.
+pub fn verify(dir_path: &path::Path) -> Result<Vec<String>, CritError> {
+    let manifest_pathbuf: path::PathBuf = dir_path.join(SHA256SUMS_JSON_FILENAME);
+    let manifest_str: String = fs::read_to_string(&manifest_pathbuf).map_err(|err| {
+        CritError::IOError(format!(
+            "unable to read {}: {err}",
+            manifest_pathbuf.display()
+        ))
+    })?;
+    let manifest: ChecksumManifest = serde_json::from_str(&manifest_str).map_err(|err| {
+        CritError::IOError(format!(
+            "unable to parse {}: {err}",
+            manifest_pathbuf.display()
+        ))
+    })?;
+    let mut problems: Vec<String> = Vec::new();
+
+    for checksum in &manifest.files {
+        let file_pathbuf: path::PathBuf = dir_path.join(&checksum.path);
+
+        if !file_pathbuf.is_file() {
+            problems.push(format!("missing: {}", checksum.path));
+            continue;
+        }
+
+        if sha256_file(&file_pathbuf)? != checksum.sha256 {
+            problems.push(format!("checksum mismatch: {}", checksum.path));
+        }
+    }
+
+    Ok(problems)
+}
@@ -7,11 +7,123 @@ extern crate regex;
 
 use die::{Die, die};
 use std::env;
+use std::path;
+
+/// targets subcommand lists enabled Rust targets.
+fn targets(arguments: &[String]) {
+    let brief: String = format!("Usage: {} targets [OPTIONS]", env!("CARGO_PKG_NAME"));
+
+    let mut opts: getopts::Options = getopts::Options::new();
+    opts.optflag("e", "explain", "list every available target with a reason");
+    opts.optopt(
+        "f",
+        "format",
+        "output format (default: table)",
+        "table|json",
+    );
+    opts.optflag("h", "help", "print usage info");
+
+    let usage: String = opts.usage(&brief);
+    let optmatches: getopts::Matches = opts.parse(arguments).die(&usage);
+
+    if optmatches.opt_present("h") {
+        die!(0; usage);
+    }
+
+    let c = match crit::Crit::read(&crit::locate_configuration().display().to_string()) {
+        Err(e) => die!(1; format!("error: {e}")),
+        Ok(e) => e,
+    };
+
+    let mut selections: Vec<crit::TargetSelection> = match c.explain_targets() {
+        Err(e) => die!(1; format!("error: {e}")),
+        Ok(e) => e,
+    };
+
+    if !optmatches.opt_present("e") {
+        selections.retain(|e| e.included);
+    }
+
+    let rendered: String = match optmatches.opt_str("f").as_deref() {
+        None | Some("table") => crit::render_selections(&selections),
+        Some("json") => match crit::render_selections_json(&selections) {
+            Err(e) => die!(1; format!("error: {e}")),
+            Ok(e) => e,
+        },
+        Some(e) => {
+            eprintln!("error: unsupported format: {e}");
+            die!(usage);
+        }
+    };
+
+    println!("{rendered}");
+}
+
+/// verify subcommand checks build outputs against their checksum manifest.
+fn verify(arguments: &[String]) {
+    let brief: String = format!("Usage: {} verify [OPTIONS] [<dir>]", env!("CARGO_PKG_NAME"));
+
+    let mut opts: getopts::Options = getopts::Options::new();
+    opts.optopt(
+        "b",
+        "banner",
+        "verify artifacts nested with a further subdirectory label",
+        "<dir>",
+    );
+    opts.optflag("h", "help", "print usage info");
+
+    let usage: String = opts.usage(&brief);
+    let optmatches: getopts::Matches = opts.parse(arguments).die(&usage);
+
+    if optmatches.opt_present("h") {
+        die!(0; usage);
+    }
+
+    if optmatches.opt_present("b") && !optmatches.free.is_empty() {
+        eprintln!("error: -b <dir> conflicts with <dir>");
+        die!(usage);
+    }
+
+    let dir_pathbuf: path::PathBuf = match optmatches.free.first() {
+        Some(e) => path::PathBuf::from(e),
+        None => match crit::Crit::read(&crit::locate_configuration().display().to_string()) {
+            Err(e) => die!(1; format!("error: {e}")),
+            Ok(mut e) => {
+                if let Some(banner) = optmatches.opt_str("b") {
+                    e.banner = Some(banner);
+                }
+
+                if let Err(err) = e.resolve_banner() {
+                    die!(1; format!("error: {err}"));
+                }
+
+                e.bin_dir()
+            }
+        },
+    };
+
+    let problems: Vec<String> = match crit::checksum::verify(&dir_pathbuf) {
+        Err(e) => die!(1; format!("error: {e}")),
+        Ok(e) => e,
+    };
+
+    if !problems.is_empty() {
+        for problem in problems {
+            eprintln!("error: {problem}");
+        }
+
+        die!(1);
+    }
+
+    eprintln!("verified {:?}", dir_pathbuf);
+}
 
 /// CLI entrypoint
 fn main() {
     let brief: String = format!(
-        "Usage: {} [OPTIONS] [-- <CROSS OPTIONS>]",
+        "Usage: {} [OPTIONS] [-- <CROSS OPTIONS>]\n       {} targets [OPTIONS]\n       {} verify [OPTIONS] [<dir>]",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_NAME"),
         env!("CARGO_PKG_NAME")
     );
 
@@ -28,18 +140,60 @@ fn main() {
         "remove artifacts directory and docker containers",
     );
     opts.optflag("d", "debug", "enable additional logging");
+    opts.optflag("n", "dry-run", "print the build matrix without building");
     opts.optflag("h", "help", "print usage info");
-    opts.optflag("v", "version", "print version info");
+    opts.optopt(
+        "j",
+        "jobs",
+        "build up to <n> targets concurrently (default: 1)",
+        "<n>",
+    );
+    opts.optflag(
+        "k",
+        "keep-going",
+        "continue building remaining targets after a failure",
+    );
+    opts.optflag(
+        "v",
+        "stream",
+        "forward live cross output, labeled by target",
+    );
+    opts.optmulti(
+        "t",
+        "target",
+        "build only the named target triple or platform alias (e.g. linux/amd64)",
+        "<target>",
+    );
+    opts.optflag("V", "version", "print version info");
+    opts.optmulti("p", "package", "build only the named package", "<name>");
+    opts.optflag("", "workspace", "build all workspace packages");
+    opts.optopt(
+        "",
+        "profile",
+        "build with the named cargo profile (default: release)",
+        "<name>",
+    );
 
     let usage: String = opts.usage(&brief);
     let arguments: Vec<String> = env::args().collect();
+
+    if arguments.get(1).map(String::as_str) == Some("targets") {
+        targets(&arguments[2..]);
+        die!(0);
+    }
+
+    if arguments.get(1).map(String::as_str) == Some("verify") {
+        verify(&arguments[2..]);
+        die!(0);
+    }
+
     let optmatches: getopts::Matches = opts.parse(&arguments[1..]).die(&usage);
 
     if optmatches.opt_present("h") {
         die!(0; usage);
     }
 
-    if optmatches.opt_present("v") {
+    if optmatches.opt_present("V") {
         die!(0; format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")));
     }
 
@@ -53,7 +207,7 @@ fn main() {
         die!(0);
     }
 
-    let mut c = match crit::Crit::load(crit::CONFIGURATION_FILENAME) {
+    let mut c = match crit::Crit::read(&crit::locate_configuration().display().to_string()) {
         Err(e) => die!(1; format!("error: {e}")),
         Ok(e) => e,
     };
@@ -72,10 +226,59 @@ fn main() {
         };
     };
 
+    if optmatches.opt_present("v") {
+        c.stream = Some(true);
+    }
+
+    if optmatches.opt_present("p") {
+        c.packages = Some(optmatches.opt_strs("p"));
+    }
+
+    if optmatches.opt_present("t") {
+        c.targets = Some(optmatches.opt_strs("t"));
+    }
+
+    if optmatches.opt_present("workspace") {
+        c.workspace = Some(true);
+    }
+
+    if optmatches.opt_present("profile") {
+        c.profile = optmatches.opt_str("profile");
+    }
+
+    if optmatches.opt_present("k") {
+        c.keep_going = Some(true);
+    }
+
+    if optmatches.opt_present("j") {
+        match optmatches.opt_str("j").map(|e| e.parse::<usize>()) {
+            Some(Ok(e)) => c.jobs = Some(e),
+            _ => {
+                eprintln!("error: invalid value for -j <n>");
+                die!(usage);
+            }
+        };
+    };
+
     if arguments.contains(&"--".to_string()) {
         c.cross_args = Some(optmatches.free.clone());
     }
 
+    // Resolve targets once the command line overrides apply.
+    if let Err(e) = c.update_targets() {
+        die!(1; format!("error: {e}"));
+    }
+
+    if optmatches.opt_present("n") {
+        match c.plan() {
+            Err(e) => die!(1; format!("error: {e}")),
+            Ok(e) => {
+                println!("{e}");
+                die!(0);
+            }
+        }
+    }
+
     if let Err(e) = c.run() {
         die!(1; format!("error: {e}"));
     }
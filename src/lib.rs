@@ -1,7 +1,21 @@
 //! crit provides predicates for conveniently managing multiple cross target builds.
 
+extern crate flate2;
+extern crate liblzma;
 extern crate regex;
+extern crate serde_json;
+extern crate tar;
 extern crate toml;
+extern crate zip;
+
+pub mod alias;
+pub mod archive;
+pub mod backend;
+pub mod cfg;
+pub mod checksum;
+pub mod macho;
+pub mod manifest;
+pub mod object;
 
 use serde::{Deserialize, Serialize};
 
@@ -9,9 +23,13 @@ use std::collections;
 use std::env;
 use std::fmt;
 use std::fs;
+use std::io;
+use std::io::{BufRead, Write};
 use std::path;
 use std::process;
 use std::sync;
+use std::thread;
+use std::time;
 
 /// CONFIGURATION_FILENAME denotes the file path to an optional TOML configuration file,
 /// relative to the current working directory.
@@ -28,17 +46,24 @@ pub static ARTIFACT_ROOT_PATH: sync::LazyLock<&path::Path> =
 pub static CROSS_DIR_PATHBUF: sync::LazyLock<path::PathBuf> =
     sync::LazyLock::new(|| ARTIFACT_ROOT_PATH.join("cross"));
 
-/// BUILD_MODES enumerates cargo's major build modes.
-pub static BUILD_MODES: sync::LazyLock<Vec<&str>> =
-    sync::LazyLock::new(|| vec!["debug", "release"]);
+/// LOGS_DIR_PATHBUF denotes the pathbuf housing per-target build logs.
+pub static LOGS_DIR_PATHBUF: sync::LazyLock<path::PathBuf> =
+    sync::LazyLock::new(|| ARTIFACT_ROOT_PATH.join("logs"));
 
-/// DEFAULT_BINARY_EXTENSIONS collects common cargo build binary file extensions.
-pub static DEFAULT_BINARY_EXTENSIONS: sync::LazyLock<Vec<String>> = sync::LazyLock::new(|| {
-    ["", "exe", "js", "wasm"]
-        .iter()
-        .map(|e| e.to_string())
-        .collect()
-});
+/// AUTO_BANNER denotes the banner shorthand for AUTO_BANNER_TEMPLATE.
+pub static AUTO_BANNER: &str = "auto";
+
+/// AUTO_BANNER_TEMPLATE denotes the banner template implied by AUTO_BANNER.
+pub static AUTO_BANNER_TEMPLATE: &str = "{name}-{version}";
+
+/// UNIVERSAL2_TARGET denotes the pseudo target of macOS universal binaries.
+pub static UNIVERSAL2_TARGET: &str = "universal2-apple-darwin";
+
+/// UNIVERSAL2_SOURCE_TARGETS denotes the targets merged into macOS universal binaries.
+pub static UNIVERSAL2_SOURCE_TARGETS: [&str; 2] = ["x86_64-apple-darwin", "aarch64-apple-darwin"];
+
+/// DEFAULT_ARCHIVE_NAME denotes the default archive file name template, sans extension.
+pub static DEFAULT_ARCHIVE_NAME: &str = "{name}-{version}-{target}";
 
 /// CritError models bad computer states.
 #[derive(Debug)]
@@ -49,6 +74,9 @@ pub enum CritError {
     UnknownMimetypeError(String),
     RegexParseError(String),
     TOMLParseError(String),
+    CfgParseError(String),
+    TargetError(String, Box<CritError>),
+    TargetErrors(Vec<CritError>),
 }
 
 impl fmt::Display for CritError {
@@ -60,6 +88,16 @@ impl fmt::Display for CritError {
             CritError::PathRenderError(e) => write!(f, "{e}"),
             CritError::RegexParseError(e) => write!(f, "{e}"),
             CritError::TOMLParseError(e) => write!(f, "{e}"),
+            CritError::CfgParseError(e) => write!(f, "{e}"),
+            CritError::TargetError(target, e) => write!(f, "{target}: {e}"),
+            CritError::TargetErrors(errs) => write!(
+                f,
+                "{}",
+                errs.iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
         }
     }
 }
@@ -181,363 +219,3773 @@ impl fmt::Display for Target {
     }
 }
 
-/// get_applications queries Cargo.toml for the list of binary application names.
-pub fn get_applications(feature_excludes: &[&str]) -> Result<Vec<String>, CritError> {
-    let bin_sections: Vec<toml::Value> = fs::read_to_string("Cargo.toml")
-        .map_err(|e| CritError::IOError(format!("unable to read Cargo.toml: {e}")))
-        .and_then(|e| {
-            e.parse::<toml::Table>()
-                .map_err(|err| CritError::TOMLParseError(err.to_string()))
-        })
-        .and_then(|e| {
-            e.get("bin")
-                .ok_or(CritError::TOMLParseError(
-                    "no binaries declared in Cargo.toml".to_string(),
-                ))
-                .cloned()
-        })
-        .and_then(|e| {
-            e.as_array()
-                .ok_or(CritError::TOMLParseError(
-                    "binary section not an array in Cargo.toml".to_string(),
-                ))
-                .cloned()
-        })?;
-
-    let name_options: Vec<Option<&toml::Value>> = bin_sections
-        .iter()
-        .filter(
-            |e| match e.get("required-features").and_then(|e2| e2.as_array()) {
-                None => true,
-                Some(feature_values) => {
-                    feature_values
-                        .iter()
-                        .map(|e2| e2.as_str())
-                        .any(|e| match e {
-                            Some(feature) => !feature_excludes.contains(&feature),
-                            None => false,
-                        })
-                }
-            },
-        )
-        .map(|e| e.get("name"))
-        .collect();
-
-    if name_options.contains(&None) {
-        return Err(CritError::TOMLParseError(
-            "binary missing name field in Cargo.toml".to_string(),
-        ));
-    }
-
-    let name_str_results: Vec<Option<&str>> = name_options
-        .iter()
-        .map(|e| {
-            let e2 = e.unwrap();
-            e2.as_str()
-        })
-        .collect();
-
-    if name_str_results.contains(&None) {
-        return Err(CritError::TOMLParseError(
-            "binary name not a string in Cargo.toml".to_string(),
-        ));
-    }
-
-    Ok(name_str_results
-        .iter()
-        .map(|e| e.unwrap())
-        .map(|e| e.to_string())
-        .collect())
-}
-
-/// Crit models a multiplatform build operation.
-#[derive(Debug, Default, Deserialize, Serialize)]
-#[serde(deny_unknown_fields)]
-pub struct Crit {
-    /// debug enables additional logging.
-    pub debug: Option<bool>,
-
-    /// banner denotes an optional parent directory prefix (e.g. "hello-1.0").
-    pub banner: Option<String>,
-
-    /// rustflags maps target triple patterns to custom RUSTFLAGS settings (default: $RUSTFLAGS).
-    pub rustflags: Option<collections::BTreeMap<String, String>>,
-
-    /// feature_excludes skips matching features.
-    pub feature_excludes: Option<Vec<String>>,
-
-    /// cross_args forwards additional flags to cross.
-    pub cross_args: Option<Vec<String>>,
+/// TargetMetadata models the target configuration reported by rustc.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct TargetMetadata {
+    /// arch denotes `target_arch`.
+    pub arch: String,
 
-    /// arch collects enabled chipets.
-    pub arch: Vec<String>,
+    /// vendor denotes `target_vendor`.
+    pub vendor: String,
 
-    /// vendor collects enabled vendors.
-    pub vendor: Vec<String>,
+    /// os denotes `target_os`.
+    pub os: String,
 
-    /// os collects enabled operating systems.
-    pub os: Vec<String>,
+    /// env denotes `target_env`.
+    pub env: String,
 
-    /// abi collects enabled chipset/libc variants.
-    pub abi: Vec<String>,
+    /// abi denotes `target_abi`.
+    pub abi: String,
 
-    /// target_excludes skips targets.
-    pub target_excludes: Option<Vec<String>>,
+    /// family collects `target_family` values.
+    pub family: Vec<String>,
 
-    /// binary_extensions selects file extensions to collate (default: `DEFAULT_BINARY_EXTENSIONS`).
-    pub binary_extensions: Option<Vec<String>>,
+    /// pointer_width denotes `target_pointer_width`.
+    pub pointer_width: String,
 
-    /// targets caches enabled Rust targets.
-    #[serde(skip)]
-    targets: Option<Vec<Target>>,
+    /// endian denotes `target_endian`.
+    pub endian: String,
 
-    /// enabled_applications caches active applications.
+    /// cfg collects every reported configuration option, as name and optional value pairs.
     #[serde(skip)]
-    enabled_applications: Option<Vec<String>>,
+    pub cfg: Vec<(String, Option<String>)>,
 }
 
-impl Crit {
-    /// load generates a Crit.
-    pub fn load(pth: &str) -> Result<Self, CritError> {
-        let toml_string = fs::read_to_string(pth)
-            .map_err(|_| CritError::IOError(format!("unable to read file: {pth}")))?;
-        let mut crit: Crit = toml::from_str(&toml_string)
-            .map_err(|e| CritError::TOMLParseError(e.message().to_string()))?;
-        crit.update_targets()?;
-        Ok(crit)
-    }
+impl TargetMetadata {
+    /// parse converts `rustc --print cfg` output to metadata.
+    pub fn parse(cfg_text: &str) -> Result<TargetMetadata, CritError> {
+        let mut metadata = TargetMetadata::default();
 
-    /// update_targets refreshes the targets cache.
-    pub fn update_targets(&mut self) -> Result<(), CritError> {
-        let target_excludes = self.target_excludes.clone().unwrap_or_default();
+        for line in cfg_text.lines().map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, value): (String, Option<String>) = match line.split_once('=') {
+                None => (line.to_string(), None),
+                Some((name, quoted_value)) => {
+                    let value: &str = quoted_value
+                        .strip_prefix('"')
+                        .and_then(|e| e.strip_suffix('"'))
+                        .ok_or(CritError::IOError(format!(
+                            "invalid rustc cfg line: {line}"
+                        )))?;
+                    (name.to_string(), Some(value.to_string()))
+                }
+            };
 
-        let mut cmd = process::Command::new("rustup");
-        cmd.args(["target", "list"]);
+            if let Some(value) = &value {
+                match name.as_str() {
+                    "target_arch" => metadata.arch = value.clone(),
+                    "target_vendor" => metadata.vendor = value.clone(),
+                    "target_os" => metadata.os = value.clone(),
+                    "target_env" => metadata.env = value.clone(),
+                    "target_abi" => metadata.abi = value.clone(),
+                    "target_family" => metadata.family.push(value.clone()),
+                    "target_pointer_width" => metadata.pointer_width = value.clone(),
+                    "target_endian" => metadata.endian = value.clone(),
+                    _ => (),
+                }
+            }
 
-        if let Some(true) = self.debug {
-            eprintln!("running command: {:?}", cmd);
+            metadata.cfg.push((name, value));
         }
 
-        let output = cmd
+        Ok(metadata)
+    }
+
+    /// query asks rustc for a target's metadata.
+    pub fn query(target: &str) -> Result<TargetMetadata, CritError> {
+        let output = process::Command::new("rustc")
+            .args(["--print", "cfg", "--target", target])
             .output()
-            .map_err(|e| CritError::IOError(e.to_string()))?;
+            .map_err(|err| CritError::IOError(format!("unable to run rustc: {err}")))?;
 
         if !output.status.success() {
             return Err(CritError::IOError(format!(
-                "failed to query rustup targets: {}",
-                output.status
+                "failed to query rustc cfg for {target}: {}",
+                String::from_utf8_lossy(&output.stderr)
             )));
         }
 
         let stdout_utf8 =
             String::from_utf8(output.stdout).map_err(|e| CritError::IOError(e.to_string()))?;
-        let mut available_targets: Vec<Target> = Vec::new();
+        TargetMetadata::parse(&stdout_utf8)
+    }
 
-        for line in stdout_utf8.lines() {
-            let target = Target::parse(line)?;
-            available_targets.push(target);
+    /// binary_filenames lists the files that cargo emits for an executable,
+    /// following the platform naming conventions of the target.
+    pub fn binary_filenames(&self, name: &str) -> Vec<String> {
+        if self.os == "windows" {
+            vec![format!("{name}.exe")]
+        } else if self.os == "uefi" {
+            vec![format!("{name}.efi")]
+        } else if self.os == "emscripten" {
+            vec![format!("{name}.js"), format!("{name}.wasm")]
+        } else if self.family.iter().any(|e| e == "wasm") {
+            vec![format!("{name}.wasm")]
+        } else {
+            vec![name.to_string()]
         }
+    }
 
-        let arches = self
-            .arch
-            .iter()
-            .cloned()
-            .collect::<collections::HashSet<String>>();
-        let vendors = self
-            .vendor
-            .iter()
-            .cloned()
-            .collect::<collections::HashSet<String>>();
-        let operating_systems = self
-            .os
-            .iter()
-            .map(|e| match e.as_str() {
-                "" => None,
-                e => Some(e.to_string()),
-            })
-            .collect::<collections::HashSet<Option<String>>>();
-        let abis = self
-            .abi
-            .iter()
-            .map(|e| match e.as_str() {
-                "" => None,
-                e => Some(e.to_string()),
-            })
-            .collect::<collections::HashSet<Option<String>>>();
+    /// library_filenames lists the files that cargo emits for a library crate type,
+    /// following the platform naming conventions of the target.
+    pub fn library_filenames(&self, name: &str, crate_type: &str) -> Vec<String> {
+        let windows: bool = self.os == "windows";
+        let msvc: bool = self.env == "msvc";
+        let wasm: bool = self.family.iter().any(|e| e == "wasm");
+        let apple: bool = self.vendor == "apple";
 
-        for arch in &arches {
-            if !available_targets.iter().any(|target| target.arch == *arch) {
-                return Err(CritError::IOError(format!("invalid arch: {:?}", arch)));
-            }
+        match crate_type {
+            "cdylib" if windows && msvc => vec![format!("{name}.dll"), format!("{name}.dll.lib")],
+            "cdylib" if windows => vec![format!("{name}.dll"), format!("lib{name}.dll.a")],
+            "cdylib" if wasm => vec![format!("{name}.wasm")],
+            "cdylib" if apple => vec![format!("lib{name}.dylib")],
+            "cdylib" => vec![format!("lib{name}.so")],
+            "staticlib" if windows && msvc => vec![format!("{name}.lib")],
+            "staticlib" => vec![format!("lib{name}.a")],
+            _ => Vec::new(),
         }
+    }
+}
 
-        for vendor in &vendors {
-            if !available_targets
-                .iter()
-                .any(|target| target.vendor == *vendor)
-            {
-                return Err(CritError::IOError(format!("invalid vendor: {:?}", vendor)));
-            }
-        }
+#[test]
+fn test_target_metadata_parsing() -> Result<(), CritError> {
+    let metadata = TargetMetadata::parse(
+        "debug_assertions
+panic=\"abort\"
+target_abi=\"\"
+target_arch=\"wasm32\"
+target_endian=\"little\"
+target_env=\"p1\"
+target_family=\"wasm\"
+target_os=\"wasi\"
+target_pointer_width=\"32\"
+target_vendor=\"unknown\"
+",
+    )?;
+    assert_eq!(metadata.arch, "wasm32");
+    assert_eq!(metadata.vendor, "unknown");
+    assert_eq!(metadata.os, "wasi");
+    assert_eq!(metadata.env, "p1");
+    assert_eq!(metadata.abi, "");
+    assert_eq!(metadata.family, vec!["wasm".to_string()]);
+    assert_eq!(metadata.pointer_width, "32");
+    assert_eq!(metadata.endian, "little");
+    assert!(
+        metadata
+            .cfg
+            .contains(&("debug_assertions".to_string(), None))
+    );
+    assert!(TargetMetadata::parse("target_os=linux").is_err());
+    Ok(())
+}
 
-        for os in &operating_systems {
-            if !available_targets.iter().any(|target| target.os == *os) {
-                return Err(CritError::IOError(format!("invalid os: {:?}", os)));
-            }
-        }
+#[test]
+fn test_artifact_filenames() {
+    let linux = TargetMetadata {
+        os: "linux".to_string(),
+        env: "gnu".to_string(),
+        family: vec!["unix".to_string()],
+        ..Default::default()
+    };
+    let macos = TargetMetadata {
+        os: "macos".to_string(),
+        vendor: "apple".to_string(),
+        family: vec!["unix".to_string()],
+        ..Default::default()
+    };
+    let windows_msvc = TargetMetadata {
+        os: "windows".to_string(),
+        env: "msvc".to_string(),
+        family: vec!["windows".to_string()],
+        ..Default::default()
+    };
+    let windows_gnu = TargetMetadata {
+        env: "gnu".to_string(),
+        ..windows_msvc.clone()
+    };
 
-        for abi in &abis {
-            if !available_targets.iter().any(|target| target.abi == *abi) {
-                return Err(CritError::IOError(format!("invalid abi: {:?}", abi)));
-            }
+    assert_eq!(linux.binary_filenames("hello"), vec!["hello"]);
+    assert_eq!(windows_gnu.binary_filenames("hello"), vec!["hello.exe"]);
+    assert_eq!(
+        TargetMetadata {
+            os: "uefi".to_string(),
+            ..Default::default()
         }
+        .binary_filenames("hello"),
+        vec!["hello.efi"]
+    );
+    assert_eq!(linux.library_filenames("ffi", "cdylib"), vec!["libffi.so"]);
+    assert_eq!(
+        linux.library_filenames("ffi", "staticlib"),
+        vec!["libffi.a"]
+    );
+    assert!(linux.library_filenames("ffi", "rlib").is_empty());
+    assert_eq!(
+        macos.library_filenames("ffi", "cdylib"),
+        vec!["libffi.dylib"]
+    );
+    assert_eq!(
+        windows_msvc.library_filenames("ffi", "cdylib"),
+        vec!["ffi.dll", "ffi.dll.lib"]
+    );
+    assert_eq!(
+        windows_msvc.library_filenames("ffi", "staticlib"),
+        vec!["ffi.lib"]
+    );
+    assert_eq!(
+        windows_gnu.library_filenames("ffi", "cdylib"),
+        vec!["ffi.dll", "libffi.dll.a"]
+    );
+    assert_eq!(
+        windows_gnu.library_filenames("ffi", "staticlib"),
+        vec!["libffi.a"]
+    );
+}
 
-        let targets = available_targets
-            .into_iter()
-            .filter(|target| {
-                arches.contains(&target.arch)
-                    && vendors.contains(&target.vendor)
-                    && operating_systems.contains(&target.os)
-                    && abis.contains(&target.abi)
-                    && !target_excludes.contains(&target.to_string())
-            })
-            .collect::<Vec<Target>>();
-        self.targets = Some(targets);
-        Ok(())
+/// rustc_target_list queries rustc for the list of built-in targets.
+pub fn rustc_target_list() -> Result<collections::HashSet<String>, CritError> {
+    let output = process::Command::new("rustc")
+        .args(["--print", "target-list"])
+        .output()
+        .map_err(|err| CritError::IOError(format!("unable to run rustc: {err}")))?;
+
+    if !output.status.success() {
+        return Err(CritError::IOError(format!(
+            "failed to query rustc targets: {}",
+            output.status
+        )));
     }
 
-    /// build_target executes a cross build.
-    pub fn build_target(&self, target: &str, bin_dir_path: &path::Path) -> Result<(), CritError> {
-        let target_dir_pathbuf = &CROSS_DIR_PATHBUF.join(target);
-        let target_dir_str: &str = &target_dir_pathbuf.display().to_string();
-        let base_args = [
-            "build",
-            "--target-dir",
-            target_dir_str,
-            "--target",
-            target,
-            // Release mode
-            "-r",
-        ]
-        .iter()
-        .map(|e| e.to_string())
-        .collect::<Vec<String>>();
-        let extra_args = self.cross_args.clone().unwrap_or_default();
-        let args = [base_args, extra_args].concat();
+    let stdout_utf8 =
+        String::from_utf8(output.stdout).map_err(|e| CritError::IOError(e.to_string()))?;
+    Ok(stdout_utf8.lines().map(|e| e.trim().to_string()).collect())
+}
 
-        let mut cmd = process::Command::new("cross");
-        cmd.args(args);
-        cmd.stdout(process::Stdio::piped());
-        cmd.stderr(process::Stdio::piped());
+/// query_target_metadata asks rustc for the metadata of many targets, concurrently.
+pub fn query_target_metadata(
+    targets: &[String],
+) -> Result<collections::BTreeMap<String, TargetMetadata>, CritError> {
+    let workers: usize = thread::available_parallelism()
+        .map(|e| e.get())
+        .unwrap_or(1)
+        .max(1);
+    let chunk_size: usize = targets.len().div_ceil(workers).max(1);
 
-        let mut rustflags: Vec<String> = match env::var("RUSTFLAGS") {
-            Ok(e) => vec![e],
-            _ => Vec::new(),
-        };
+    thread::scope(|scope| {
+        let handles = targets
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|target| TargetMetadata::query(target).map(|e| (target.clone(), e)))
+                        .collect::<Result<Vec<(String, TargetMetadata)>, CritError>>()
+                })
+            })
+            .collect::<Vec<_>>();
 
-        for (target_pattern_string, rf) in self.rustflags.clone().unwrap_or_default().into_iter() {
-            let target_pattern_regex = regex::Regex::new(&target_pattern_string)
-                .map_err(|e| CritError::RegexParseError(e.to_string()))?;
+        let mut metadata: collections::BTreeMap<String, TargetMetadata> =
+            collections::BTreeMap::new();
 
-            if target_pattern_regex.is_match(target) {
-                rustflags.push(rf);
-            }
+        for handle in handles {
+            let results = handle
+                .join()
+                .map_err(|_| CritError::IOError("unable to query rustc cfg".to_string()))??;
+            metadata.extend(results);
         }
 
-        cmd.env("RUSTFLAGS", rustflags.join(" "));
+        Ok(metadata)
+    })
+}
+
+/// compile_target_pattern converts target name patterns to regular expressions.
+///
+/// Patterns beginning with `^` or ending with `$` use Rust regex notation.
+/// Other patterns are globs, where `*` matches any run of characters, and `?` matches any single character.
+/// Patterns without wildcards match exact target names.
+pub fn compile_target_pattern(pattern: &str) -> Result<regex::Regex, CritError> {
+    let regex_string: String = if pattern.starts_with('^') || pattern.ends_with('$') {
+        pattern.to_string()
+    } else {
+        let translated: String = pattern
+            .split('*')
+            .map(|e| {
+                e.split('?')
+                    .map(regex::escape)
+                    .collect::<Vec<String>>()
+                    .join(".")
+            })
+            .collect::<Vec<String>>()
+            .join(".*");
+        format!("^{translated}$")
+    };
 
-        if let Some(true) = self.debug {
-            eprintln!("debug: running command: {:?}", cmd);
-        }
+    regex::Regex::new(&regex_string).map_err(|err| {
+        CritError::RegexParseError(format!("invalid target pattern {pattern:?}: {err}"))
+    })
+}
 
-        let cross_output: process::Output = cmd
-            .output()
-            .map_err(|err| CritError::IOError(format!("unable to run cross: {err}")))?;
+#[test]
+fn test_target_patterns() -> Result<(), CritError> {
+    let glob = compile_target_pattern("*-windows-gnu")?;
+    assert!(glob.is_match("x86_64-pc-windows-gnu"));
+    assert!(!glob.is_match("x86_64-pc-windows-gnullvm"));
+
+    let exact = compile_target_pattern("thumbv8m.base-none-eabi")?;
+    assert!(exact.is_match("thumbv8m.base-none-eabi"));
+    assert!(!exact.is_match("thumbv8mxbase-none-eabi"));
+    assert!(!exact.is_match("thumbv8m.base-none-eabihf"));
+
+    let single = compile_target_pattern("i?86-*")?;
+    assert!(single.is_match("i686-pc-windows-msvc"));
+    assert!(single.is_match("i586-unknown-linux-gnu"));
+
+    let regex = compile_target_pattern("^riscv.*musl$")?;
+    assert!(regex.is_match("riscv64gc-unknown-linux-musl"));
+    assert!(!regex.is_match("riscv64gc-unknown-linux-gnu"));
+
+    assert!(compile_target_pattern("^riscv(").is_err());
+    Ok(())
+}
 
-        if !cross_output.status.success() {
-            let cross_stderr: String = String::from_utf8(cross_output.stderr)
-                .map_err(|err| CritError::IOError(format!("unable to decode stderr: {err}")))?;
+/// match_target_patterns resolves the value that a map of target patterns assigns to a target.
+///
+/// When several patterns match a target, they must agree on the value.
+pub fn match_target_patterns<'a>(
+    patterns: Option<&'a collections::BTreeMap<String, String>>,
+    target: &str,
+    setting: &str,
+) -> Result<Option<&'a str>, CritError> {
+    let mut selection: Option<(&str, &str)> = None;
 
-            return Err(CritError::IOError(cross_stderr));
+    for (target_pattern_string, value) in patterns.into_iter().flatten() {
+        if !compile_target_pattern(target_pattern_string)?.is_match(target) {
+            continue;
         }
 
-        let enabled_applications: Vec<String> =
-            self.enabled_applications.clone().unwrap_or_default();
+        if let Some((previous_pattern, previous_value)) = selection
+            && previous_value != value
+        {
+            return Err(CritError::IOError(format!(
+                "conflicting {setting} for {target}: {previous_pattern:?} selects {previous_value}, {target_pattern_string:?} selects {value}"
+            )));
+        }
 
-        let binary_extensions_strings: Vec<String> = self
-            .binary_extensions
-            .clone()
-            .unwrap_or(DEFAULT_BINARY_EXTENSIONS.clone());
-        let binary_extensions_strs = binary_extensions_strings
-            .iter()
-            .map(|e| e.as_str())
-            .collect::<Vec<&str>>();
+        selection = Some((target_pattern_string, value));
+    }
 
-        for application in enabled_applications {
-            let dest_dir_pathbuf: path::PathBuf = bin_dir_path.join(target);
-            let dest_dir_str: &str = &dest_dir_pathbuf.display().to_string();
+    Ok(selection.map(|(_, value)| value))
+}
 
-            fs::create_dir_all(dest_dir_str).map_err(|err| CritError::IOError(err.to_string()))?;
+#[test]
+fn test_match_target_patterns() -> Result<(), CritError> {
+    let patterns: collections::BTreeMap<String, String> = [
+        ("*-linux-gnu".to_string(), "zigbuild".to_string()),
+        ("aarch64-*".to_string(), "zigbuild".to_string()),
+        ("wasm32-*".to_string(), "cargo".to_string()),
+    ]
+    .into();
+    let backend = |target: &str| match_target_patterns(Some(&patterns), target, "backends");
+    assert_eq!(backend("aarch64-unknown-linux-gnu")?, Some("zigbuild"));
+    assert_eq!(backend("wasm32-unknown-unknown")?, Some("cargo"));
+    assert_eq!(backend("x86_64-pc-windows-msvc")?, None);
 
-            for extension in &binary_extensions_strs {
-                for mode in BUILD_MODES.clone() {
-                    let mut source_pathbuf: path::PathBuf = target_dir_pathbuf
-                        .join(target)
-                        .join(mode)
-                        .join(&application);
-                    source_pathbuf.set_extension(extension);
+    let conflicting: collections::BTreeMap<String, String> = [
+        ("x86_64-unknown-linux-gnu".to_string(), "cargo".to_string()),
+        ("*-linux-gnu".to_string(), "zigbuild".to_string()),
+    ]
+    .into();
+    assert!(
+        match_target_patterns(Some(&conflicting), "x86_64-unknown-linux-gnu", "backends").is_err()
+    );
+    assert_eq!(
+        match_target_patterns(Some(&conflicting), "i686-unknown-linux-gnu", "backends")?,
+        Some("zigbuild")
+    );
+    Ok(())
+}
 
-                    if source_pathbuf.exists() {
-                        let source_str: &str = &source_pathbuf.display().to_string();
+/// DimensionSource selects how crit reads the arch, vendor, os, env, and abi of a target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DimensionSource {
+    /// Rustc reads target metadata from `rustc --print cfg`.
+    Rustc,
 
-                        let mut dest_pathbuf: path::PathBuf = dest_dir_pathbuf.join(&application);
-                        dest_pathbuf.set_extension(extension);
-                        let dest_str: &str = &dest_pathbuf.display().to_string();
+    /// Triple reads target dimensions by position from the target identifier.
+    Triple,
+}
 
-                        fs::copy(source_str, dest_str)
-                            .map_err(|err| CritError::IOError(err.to_string()))?;
-                    }
-                }
-            }
+impl DimensionSource {
+    /// parse converts configuration strings to dimension sources.
+    pub fn parse(s: &str) -> Result<DimensionSource, CritError> {
+        match s {
+            "rustc" => Ok(DimensionSource::Rustc),
+            "triple" => Ok(DimensionSource::Triple),
+            _ => Err(CritError::IOError(format!(
+                "unsupported dimension_source: {s}"
+            ))),
         }
-
-        Ok(())
     }
+}
 
-    /// run builds targets.
-    pub fn run(&mut self) -> Result<(), CritError> {
-        let targets = self.targets.clone().unwrap_or_default();
+/// TargetSelection explains whether a target is enabled.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TargetSelection {
+    /// triple denotes a Rust target identifier.
+    pub triple: String,
 
-        if targets.is_empty() {
-            eprintln!("warning: empty targets");
-            return Ok(());
-        }
+    /// target denotes the parsed Rust target identifier.
+    pub target: Target,
 
-        let feature_excludes_strings = self.feature_excludes.clone().unwrap_or_default();
-        let feature_excludes_strs = feature_excludes_strings
-            .iter()
-            .map(String::as_str)
-            .collect::<Vec<&str>>();
-        self.enabled_applications = Some(get_applications(&feature_excludes_strs)?);
+    /// metadata denotes rustc target metadata, when available.
+    pub metadata: Option<TargetMetadata>,
 
-        let bin_dir_pathbuf = if let Some(banner) = &self.banner
-            && !banner.is_empty()
-        {
-            &ARTIFACT_ROOT_PATH.join("bin").join(banner)
-        } else {
-            &ARTIFACT_ROOT_PATH.join("bin")
+    /// included reports whether the target is enabled.
+    pub included: bool,
+
+    /// reason describes why the target is enabled or disabled.
+    pub reason: String,
+}
+
+/// render_selections formats target selections as a table.
+pub fn render_selections(selections: &[TargetSelection]) -> String {
+    let triple_width = selections
+        .iter()
+        .map(|e| e.triple.len())
+        .chain(["TARGET".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut lines: Vec<String> = vec![format!("{:<triple_width$}  REASON", "TARGET")];
+
+    for selection in selections {
+        lines.push(format!(
+            "{:<triple_width$}  {}",
+            selection.triple, selection.reason
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// render_selections_json formats target selections as JSON.
+pub fn render_selections_json(selections: &[TargetSelection]) -> Result<String, CritError> {
+    serde_json::to_string_pretty(selections)
+        .map_err(|err| CritError::IOError(format!("unable to render JSON: {err}")))
+}
+
+#[test]
+fn test_render_selections() -> Result<(), CritError> {
+    let selection =
+        |triple: &str, included: bool, reason: &str| -> Result<TargetSelection, CritError> {
+            Ok(TargetSelection {
+                triple: triple.to_string(),
+                target: Target::parse(triple)?,
+                metadata: None,
+                included,
+                reason: reason.to_string(),
+            })
         };
+    let selections: Vec<TargetSelection> = vec![
+        selection("aarch64-apple-darwin", true, "included")?,
+        selection(
+            "x86_64-pc-windows-msvc",
+            false,
+            "excluded: vendor 'pc' not enabled",
+        )?,
+    ];
+    assert_eq!(
+        render_selections(&selections),
+        [
+            "TARGET                  REASON",
+            "aarch64-apple-darwin    included",
+            "x86_64-pc-windows-msvc  excluded: vendor 'pc' not enabled",
+        ]
+        .join("\n")
+    );
+    assert_eq!(render_selections(&[]), "TARGET  REASON");
+    Ok(())
+}
 
-        for target in targets {
-            eprintln!("building {target}");
-            self.build_target(&target.to_string(), bin_dir_pathbuf)?;
-        }
+/// TargetStatus models the outcome of a target build.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TargetStatus {
+    Succeeded,
+    Failed,
+    Skipped,
+}
 
-        eprintln!("artifacts copied to {:?}", bin_dir_pathbuf);
-        Ok(())
+impl fmt::Display for TargetStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TargetStatus::Succeeded => write!(f, "succeeded"),
+            TargetStatus::Failed => write!(f, "failed"),
+            TargetStatus::Skipped => write!(f, "skipped"),
+        }
     }
 }
 
+/// TargetReport summarizes a target build.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetReport {
+    /// target denotes a Rust target triple.
+    pub target: String,
+
+    /// status denotes the build outcome.
+    pub status: TargetStatus,
+
+    /// duration denotes the elapsed build time.
+    pub duration: time::Duration,
+}
+
+/// render_summary formats target build reports as a table.
+pub fn render_summary(reports: &[TargetReport]) -> String {
+    let target_width = reports
+        .iter()
+        .map(|e| e.target.len())
+        .chain(["TARGET".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut lines: Vec<String> = vec![format!(
+        "{:<target_width$}  {:<9}  DURATION",
+        "TARGET", "STATUS"
+    )];
+
+    for report in reports {
+        lines.push(format!(
+            "{:<target_width$}  {:<9}  {:.1}s",
+            report.target,
+            report.status.to_string(),
+            report.duration.as_secs_f64()
+        ));
+    }
+
+    let count = |status: TargetStatus| reports.iter().filter(|e| e.status == status).count();
+
+    lines.push(format!(
+        "{} succeeded, {} failed, {} skipped",
+        count(TargetStatus::Succeeded),
+        count(TargetStatus::Failed),
+        count(TargetStatus::Skipped)
+    ));
+    lines.join("\n")
+}
+
+#[test]
+fn test_render_summary() {
+    let reports = vec![
+        TargetReport {
+            target: "aarch64-apple-darwin".to_string(),
+            status: TargetStatus::Succeeded,
+            duration: time::Duration::from_millis(12_340),
+        },
+        TargetReport {
+            target: "powerpc64le-unknown-linux-musl".to_string(),
+            status: TargetStatus::Failed,
+            duration: time::Duration::from_millis(500),
+        },
+        TargetReport {
+            target: "x86_64-pc-windows-gnu".to_string(),
+            status: TargetStatus::Skipped,
+            duration: time::Duration::ZERO,
+        },
+    ];
+
+    assert_eq!(
+        render_summary(&reports),
+        [
+            "TARGET                          STATUS     DURATION",
+            "aarch64-apple-darwin            succeeded  12.3s",
+            "powerpc64le-unknown-linux-musl  failed     0.5s",
+            "x86_64-pc-windows-gnu           skipped    0.0s",
+            "1 succeeded, 1 failed, 1 skipped",
+        ]
+        .join("\n")
+    );
+}
+
+/// CargoTarget models a build target reported by `cargo metadata`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CargoTarget {
+    /// name denotes the target name.
+    pub name: String,
+
+    /// kind collects target kinds (e.g. "bin", "lib", "cdylib").
+    pub kind: Vec<String>,
+
+    /// crate_types collects crate types.
+    #[serde(default)]
+    pub crate_types: Vec<String>,
+
+    /// required_features collects features that the target requires.
+    #[serde(rename = "required-features", default)]
+    pub required_features: Vec<String>,
+}
+
+/// CargoPackage models a package reported by `cargo metadata`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CargoPackage {
+    /// name denotes the package name.
+    pub name: String,
+
+    /// version denotes the package version.
+    pub version: String,
+
+    /// id denotes the package identifier.
+    pub id: String,
+
+    /// manifest_path denotes the location of the package Cargo.toml.
+    pub manifest_path: String,
+
+    /// targets collects the package build targets.
+    pub targets: Vec<CargoTarget>,
+
+    /// features maps the package features to their dependent features.
+    #[serde(default)]
+    pub features: collections::BTreeMap<String, Vec<String>>,
+}
+
+/// CargoMetadata models `cargo metadata --format-version 1 --no-deps` output.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CargoMetadata {
+    /// packages collects workspace packages.
+    pub packages: Vec<CargoPackage>,
+
+    /// workspace_members collects workspace package identifiers.
+    pub workspace_members: Vec<String>,
+
+    /// workspace_default_members collects the package identifiers that cargo builds by default.
+    #[serde(default)]
+    pub workspace_default_members: Vec<String>,
+
+    /// workspace_root denotes the workspace directory.
+    pub workspace_root: String,
+}
+
+impl CargoMetadata {
+    /// query asks cargo for the package metadata of the current directory.
+    pub fn query() -> Result<CargoMetadata, CritError> {
+        let output = process::Command::new("cargo")
+            .args(["metadata", "--format-version", "1", "--no-deps"])
+            .output()
+            .map_err(|err| CritError::IOError(format!("unable to run cargo metadata: {err}")))?;
+
+        if !output.status.success() {
+            return Err(CritError::IOError(format!(
+                "failed to query cargo metadata: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map_err(|err| CritError::IOError(format!("unable to parse cargo metadata: {err}")))
+    }
+
+    /// current_package selects the package whose manifest resides in the current directory, if any.
+    ///
+    /// Virtual workspace manifests lack a current package.
+    pub fn current_package(&self) -> Result<Option<&CargoPackage>, CritError> {
+        for package in &self.packages {
+            if package.is_current()? {
+                return Ok(Some(package));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// package looks up a workspace package by name.
+    pub fn package(&self, name: &str) -> Result<&CargoPackage, CritError> {
+        self.packages
+            .iter()
+            .find(|e| e.name == name)
+            .ok_or(CritError::IOError(format!("unknown package: {name}")))
+    }
+
+    /// members looks up workspace packages by identifier.
+    pub fn members(&self, ids: &[String]) -> Vec<&CargoPackage> {
+        self.packages
+            .iter()
+            .filter(|e| ids.contains(&e.id))
+            .collect()
+    }
+}
+
+/// git_describe labels the current git checkout, e.g. "v1.0.0-2-g1a2b3c4-dirty".
+pub fn git_describe() -> Result<String, CritError> {
+    let output = process::Command::new("git")
+        .args(["describe", "--tags", "--always", "--dirty"])
+        .output()
+        .map_err(|err| CritError::IOError(format!("unable to run git describe: {err}")))?;
+
+    if !output.status.success() {
+        return Err(CritError::IOError(format!(
+            "failed to describe git checkout: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+impl CargoPackage {
+    /// manifest_dir denotes the directory housing the package Cargo.toml.
+    pub fn manifest_dir(&self) -> Option<path::PathBuf> {
+        path::Path::new(&self.manifest_path)
+            .parent()
+            .map(|e| e.to_path_buf())
+    }
+
+    /// is_current reports whether the package manifest resides in the current directory.
+    pub fn is_current(&self) -> Result<bool, CritError> {
+        let cwd: path::PathBuf = canonical_current_dir()?;
+        Ok(self
+            .manifest_dir()
+            .and_then(|e| fs::canonicalize(e).ok())
+            .as_ref()
+            == Some(&cwd))
+    }
+
+    /// member_configuration reads the crit.toml of a workspace member, if any.
+    ///
+    /// A crit.toml serving as the root configuration is no member configuration.
+    /// Members may only override MEMBER_CONFIGURATION_KEYS. crit warns about, and ignores, other keys.
+    pub fn member_configuration(
+        &self,
+        root_configuration: Option<&path::Path>,
+    ) -> Result<Option<Crit>, CritError> {
+        let member_config_pathbuf: path::PathBuf = match self.manifest_dir() {
+            Some(e) => e.join(CONFIGURATION_FILENAME),
+            None => return Ok(None),
+        };
+
+        if !member_config_pathbuf.exists()
+            || fs::canonicalize(&member_config_pathbuf).ok().as_deref() == root_configuration
+        {
+            return Ok(None);
+        }
+
+        let member_config_str: &str = &member_config_pathbuf.display().to_string();
+        let toml_string = fs::read_to_string(&member_config_pathbuf)
+            .map_err(|_| CritError::IOError(format!("unable to read file: {member_config_str}")))?;
+        let member_table: toml::Table = toml::from_str(&toml_string).map_err(|e| {
+            CritError::TOMLParseError(format!("{member_config_str}: {}", e.message()))
+        })?;
+
+        for key in member_table
+            .keys()
+            .filter(|e| !MEMBER_CONFIGURATION_KEYS.contains(&e.as_str()))
+        {
+            eprintln!(
+                "warning: {member_config_str}: ignoring {key}, which only the root configuration sets"
+            );
+        }
+
+        member_table
+            .try_into()
+            .map(Some)
+            .map_err(|e: toml::de::Error| {
+                CritError::TOMLParseError(format!("{member_config_str}: {}", e.message()))
+            })
+    }
+}
+
+/// MEMBER_CONFIGURATION_KEYS collects the configuration keys that workspace members may override.
+pub static MEMBER_CONFIGURATION_KEYS: [&str; 1] = ["feature_excludes"];
+
+/// locate_configuration resolves the crit configuration file path.
+///
+/// Prefers a crit.toml in the cargo workspace root,
+/// falling back to a crit.toml in the current directory.
+/// Any crit.toml of a workspace member then applies as a member configuration.
+pub fn locate_configuration() -> path::PathBuf {
+    let local_pathbuf: path::PathBuf = path::PathBuf::from(CONFIGURATION_FILENAME);
+
+    match CargoMetadata::query() {
+        Ok(metadata) => {
+            let workspace_pathbuf: path::PathBuf =
+                path::Path::new(&metadata.workspace_root).join(CONFIGURATION_FILENAME);
+
+            if workspace_pathbuf.exists() {
+                workspace_pathbuf
+            } else {
+                local_pathbuf
+            }
+        }
+        _ => local_pathbuf,
+    }
+}
+
+/// canonical_current_dir resolves the current working directory.
+fn canonical_current_dir() -> Result<path::PathBuf, CritError> {
+    env::current_dir()
+        .and_then(fs::canonicalize)
+        .map_err(|err| CritError::IOError(format!("unable to resolve current directory: {err}")))
+}
+
+/// LIBRARY_CRATE_TYPES collects the library crate types that crit collates.
+pub static LIBRARY_CRATE_TYPES: [&str; 2] = ["cdylib", "staticlib"];
+
+/// Application models a binary or library build target.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Application {
+    /// package denotes the cargo package name.
+    pub package: String,
+
+    /// name denotes the binary name.
+    pub name: String,
+
+    /// required_features collects the features that cargo requires to build the binary.
+    pub required_features: Vec<String>,
+
+    /// crate_types collects the collated crate types ("bin", "cdylib", "staticlib").
+    pub crate_types: Vec<String>,
+}
+
+impl Application {
+    /// is_binary reports whether the application is an executable.
+    pub fn is_binary(&self) -> bool {
+        self.crate_types.iter().any(|e| e == "bin")
+    }
+}
+
+/// get_applications lists the binary and C-compatible library applications of a package.
+///
+/// Cargo only builds a binary when all of its required features are enabled,
+/// so binaries requiring any excluded feature are skipped.
+pub fn get_applications(package: &CargoPackage, feature_excludes: &[&str]) -> Vec<Application> {
+    let mut applications: Vec<Application> = package
+        .targets
+        .iter()
+        .filter(|e| e.kind.iter().any(|kind| kind == "bin"))
+        .filter(|e| {
+            !e.required_features
+                .iter()
+                .any(|feature| feature_excludes.contains(&feature.as_str()))
+        })
+        .map(|e| Application {
+            package: package.name.clone(),
+            name: e.name.clone(),
+            required_features: e.required_features.clone(),
+            crate_types: vec!["bin".to_string()],
+        })
+        .collect();
+
+    for target in &package.targets {
+        let crate_types: Vec<String> = target
+            .crate_types
+            .iter()
+            .filter(|e| LIBRARY_CRATE_TYPES.contains(&e.as_str()))
+            .cloned()
+            .collect();
+
+        if crate_types.is_empty() {
+            continue;
+        }
+
+        applications.push(Application {
+            package: package.name.clone(),
+            name: target.name.replace('-', "_"),
+            required_features: Vec::new(),
+            crate_types,
+        });
+    }
+
+    applications
+}
+
+/// enabled_features resolves the package features that a feature set enables,
+/// following the package feature graph.
+pub fn enabled_features(
+    package: &CargoPackage,
+    feature_set: &[String],
+) -> collections::BTreeSet<String> {
+    let mut features: collections::BTreeSet<String> = collections::BTreeSet::new();
+    let mut pending: Vec<String> = feature_set.to_vec();
+
+    while let Some(feature) = pending.pop() {
+        if let Some(dependent_features) = package.features.get(&feature)
+            && !features.contains(&feature)
+        {
+            pending.extend(dependent_features.iter().cloned());
+        }
+
+        features.insert(feature);
+    }
+
+    features
+}
+
+/// render_template substitutes `{placeholder}` fields in a template.
+pub fn render_template(
+    template: &str,
+    values: &collections::BTreeMap<&str, String>,
+) -> Result<String, CritError> {
+    let mut rendered: String = String::new();
+    let mut rest: &str = template;
+
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+
+        let end: usize = rest[start..]
+            .find('}')
+            .map(|e| start + e)
+            .ok_or(CritError::IOError(format!(
+                "unterminated placeholder in template: {template}"
+            )))?;
+        let placeholder: &str = &rest[start + 1..end];
+        let value: &String = values.get(placeholder).ok_or(CritError::IOError(format!(
+            "unknown placeholder {{{placeholder}}} in template: {template}"
+        )))?;
+        rendered.push_str(value);
+        rest = &rest[end + 1..];
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// render_filename renders a file name template, rejecting path separators.
+pub fn render_filename(
+    template: &str,
+    values: &collections::BTreeMap<&str, String>,
+) -> Result<String, CritError> {
+    let rendered: String = render_template(template, values)?;
+
+    if rendered.contains(['/', '\\']) {
+        return Err(CritError::PathRenderError(format!(
+            "file name template {template:?} renders {rendered:?}, containing a path separator (for platform aliases, use {{os_alias}}-{{arch_alias}} in place of {{alias}})"
+        )));
+    }
+
+    Ok(rendered)
+}
+
+#[test]
+fn test_render_template() -> Result<(), CritError> {
+    let values: collections::BTreeMap<&str, String> = [
+        ("name", "hello".to_string()),
+        ("version", "0.0.1".to_string()),
+        ("target", "x86_64-unknown-linux-gnu".to_string()),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        render_template(DEFAULT_ARCHIVE_NAME, &values)?,
+        "hello-0.0.1-x86_64-unknown-linux-gnu"
+    );
+    assert_eq!(render_template("release", &values)?, "release");
+    assert!(render_template("{name}-{commit}", &values).is_err());
+    assert!(render_template("{name", &values).is_err());
+
+    let alias_values: collections::BTreeMap<&str, String> =
+        [("alias", "linux/amd64".to_string())].into_iter().collect();
+    assert_eq!(render_template("{alias}", &alias_values)?, "linux/amd64");
+    assert!(render_filename("hello-{alias}", &alias_values).is_err());
+    assert_eq!(
+        render_filename("{name}-{target}", &values)?,
+        "hello-x86_64-unknown-linux-gnu"
+    );
+    Ok(())
+}
+
+/// Build pairs a target triple with an optional feature set.
+pub type Build = (String, Option<Vec<String>>);
+
+/// UniversalBinary pairs a universal binary path with its (rustc arch, source path) slices.
+pub type UniversalBinary = (path::PathBuf, Vec<(String, path::PathBuf)>);
+
+/// schedule_builds runs builds across up to `jobs` workers, taking builds in matrix order.
+///
+/// Reports follow matrix order. Unless keep_going, a failed build stops workers from
+/// starting further builds, which report as skipped.
+pub fn schedule_builds<F>(
+    builds: &[Build],
+    jobs: usize,
+    keep_going: bool,
+    build: F,
+) -> Result<(Vec<TargetReport>, Vec<CritError>), CritError>
+where
+    F: Fn(&str, Option<&[String]>) -> Result<(), CritError> + Sync,
+{
+    if jobs == 0 {
+        return Err(CritError::IOError("jobs must be at least 1".to_string()));
+    }
+
+    let halted = sync::atomic::AtomicBool::new(false);
+    let queue: sync::Mutex<collections::VecDeque<(usize, Build)>> =
+        sync::Mutex::new(builds.iter().cloned().enumerate().collect());
+    let results: sync::Mutex<Vec<(usize, TargetReport, Option<CritError>)>> =
+        sync::Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                loop {
+                    if halted.load(sync::atomic::Ordering::SeqCst) {
+                        return;
+                    }
+
+                    let (index, (target, feature_set)) =
+                        match queue.lock().ok().and_then(|mut e| e.pop_front()) {
+                            Some(e) => e,
+                            None => return,
+                        };
+                    let feature_set: Option<&[String]> = feature_set.as_deref();
+                    let build_label: String = Crit::build_label(&target, feature_set);
+
+                    eprintln!("building {build_label}");
+
+                    let start = time::Instant::now();
+                    let result = build(&target, feature_set);
+                    let duration = start.elapsed();
+
+                    let (status, err) = match result {
+                        Ok(_) => (TargetStatus::Succeeded, None),
+                        Err(err) => {
+                            if !keep_going {
+                                halted.store(true, sync::atomic::Ordering::SeqCst);
+                            }
+
+                            eprintln!("error: failed to build {build_label}");
+                            (
+                                TargetStatus::Failed,
+                                Some(CritError::TargetError(build_label.clone(), Box::new(err))),
+                            )
+                        }
+                    };
+
+                    if let Ok(mut e) = results.lock() {
+                        e.push((
+                            index,
+                            TargetReport {
+                                target: build_label,
+                                status,
+                                duration,
+                            },
+                            err,
+                        ));
+                    }
+                }
+            });
+        }
+    });
+
+    let mut results = results
+        .into_inner()
+        .map_err(|err| CritError::IOError(format!("unable to collect build results: {err}")))?;
+    results.sort_by_key(|(index, _, _)| *index);
+
+    let mut reports: Vec<TargetReport> = Vec::new();
+    let mut failures: Vec<CritError> = Vec::new();
+    let mut results_iter = results.into_iter().peekable();
+
+    for (index, (target, feature_set)) in builds.iter().enumerate() {
+        match results_iter.next_if(|(i, _, _)| *i == index) {
+            Some((_, report, err)) => {
+                reports.push(report);
+                failures.extend(err);
+            }
+            None => reports.push(TargetReport {
+                target: Crit::build_label(target, feature_set.as_deref()),
+                status: TargetStatus::Skipped,
+                duration: time::Duration::ZERO,
+            }),
+        }
+    }
+
+    Ok((reports, failures))
+}
+
+#[test]
+fn test_schedule_builds() -> Result<(), CritError> {
+    let builds: Vec<Build> = ["a", "b", "c", "d"]
+        .iter()
+        .map(|e| (e.to_string(), None))
+        .collect();
+    let schedule = |jobs: usize, keep_going: bool| {
+        let started: sync::Mutex<Vec<String>> = sync::Mutex::new(Vec::new());
+        let (reports, failures) = schedule_builds(&builds, jobs, keep_going, |target, _| {
+            started.lock().unwrap().push(target.to_string());
+
+            match target {
+                "b" => Err(CritError::IOError("boom".to_string())),
+                _ => Ok(()),
+            }
+        })?;
+        let statuses: Vec<(String, TargetStatus)> = reports
+            .iter()
+            .map(|e| (e.target.clone(), e.status))
+            .collect();
+        let summary: String = render_summary(&reports).lines().last().unwrap().to_string();
+        Ok::<_, CritError>((
+            started.into_inner().unwrap(),
+            statuses,
+            failures.len(),
+            summary,
+        ))
+    };
+    let status = |target: &str, status: TargetStatus| (target.to_string(), status);
+
+    let (started, statuses, failure_count, summary) = schedule(1, false)?;
+    assert_eq!(started, vec!["a", "b"]);
+    assert_eq!(
+        statuses,
+        vec![
+            status("a", TargetStatus::Succeeded),
+            status("b", TargetStatus::Failed),
+            status("c", TargetStatus::Skipped),
+            status("d", TargetStatus::Skipped),
+        ]
+    );
+    assert_eq!(failure_count, 1);
+    assert_eq!(summary, "1 succeeded, 1 failed, 2 skipped");
+
+    let (started, statuses, failure_count, summary) = schedule(1, true)?;
+    assert_eq!(started, vec!["a", "b", "c", "d"]);
+    assert_eq!(statuses[3], status("d", TargetStatus::Succeeded));
+    assert_eq!(failure_count, 1);
+    assert_eq!(summary, "3 succeeded, 1 failed, 0 skipped");
+
+    let (mut started, statuses, _, summary) = schedule(3, true)?;
+    started.sort();
+    assert_eq!(started, vec!["a", "b", "c", "d"]);
+    assert_eq!(
+        statuses
+            .iter()
+            .map(|(e, _)| e.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["a", "b", "c", "d"]
+    );
+    assert_eq!(summary, "3 succeeded, 1 failed, 0 skipped");
+
+    assert!(schedule_builds(&builds, 0, false, |_, _| Ok(())).is_err());
+    Ok(())
+}
+
+/// feature_set_label names a feature set, for use in directory names and reports.
+pub fn feature_set_label(feature_set: &[String]) -> String {
+    if feature_set.is_empty() {
+        "no-default-features".to_string()
+    } else {
+        feature_set.join("+")
+    }
+}
+
+#[test]
+fn test_cargo_metadata_parsing() -> Result<(), CritError> {
+    let metadata: CargoMetadata = serde_json::from_str(
+        r#"{
+            "packages": [{
+                "name": "demo",
+                "version": "0.0.1",
+                "id": "path+file:///src/demo#0.0.1",
+                "manifest_path": "/src/demo/Cargo.toml",
+                "targets": [
+                    {"kind": ["bin"], "crate_types": ["bin"], "name": "demo", "src_path": "/src/demo/src/main.rs"},
+                    {"kind": ["bin"], "crate_types": ["bin"], "name": "letmeout", "required-features": ["letmeout"], "src_path": "/src/demo/src/bin/letmeout.rs"}
+                ]
+            }],
+            "workspace_members": ["path+file:///src/demo#0.0.1"],
+            "workspace_root": "/src/demo",
+            "version": 1
+        }"#,
+    )
+    .map_err(|err| CritError::IOError(err.to_string()))?;
+
+    let targets = &metadata.packages[0].targets;
+    assert_eq!(targets[0].name, "demo");
+    assert!(targets[0].required_features.is_empty());
+    assert_eq!(targets[1].required_features, vec!["letmeout".to_string()]);
+
+    let application_names = |feature_excludes: &[&str]| {
+        get_applications(&metadata.packages[0], feature_excludes)
+            .into_iter()
+            .map(|e| e.name)
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(application_names(&[]), vec!["demo", "letmeout"]);
+    assert_eq!(application_names(&["letmeout"]), vec!["demo"]);
+    assert_eq!(application_names(&["other"]), vec!["demo", "letmeout"]);
+    Ok(())
+}
+
+/// LOGGED_ENV_PREFIXES collects the prefixes of inherited environment variables that affect builds.
+pub static LOGGED_ENV_PREFIXES: [&str; 3] = ["CARGO_", "RUST", "CROSS_"];
+
+/// effective_env lists the build relevant environment of a command:
+/// PATH and any inherited LOGGED_ENV_PREFIXES variables, with the command overrides applied.
+pub fn effective_env(cmd: &process::Command) -> collections::BTreeMap<String, String> {
+    let mut vars: collections::BTreeMap<String, String> = env::vars_os()
+        .map(|(key, value)| {
+            (
+                key.to_string_lossy().to_string(),
+                value.to_string_lossy().to_string(),
+            )
+        })
+        .filter(|(key, _)| key == "PATH" || LOGGED_ENV_PREFIXES.iter().any(|e| key.starts_with(e)))
+        .collect();
+
+    for (key, value) in cmd.get_envs() {
+        let key: String = key.to_string_lossy().to_string();
+
+        match value {
+            Some(value) => {
+                vars.insert(key, value.to_string_lossy().to_string());
+            }
+            None => {
+                vars.remove(&key);
+            }
+        }
+    }
+
+    vars
+}
+
+#[test]
+fn test_effective_env() {
+    let mut cmd: process::Command = process::Command::new("cargo");
+    cmd.env("RUSTFLAGS", "-Ctarget-feature=+crt-static");
+    cmd.env("CRIT_TEST_OVERRIDE", "1");
+    cmd.env_remove("PATH");
+    let vars: collections::BTreeMap<String, String> = effective_env(&cmd);
+    assert_eq!(
+        vars.get("RUSTFLAGS").map(String::as_str),
+        Some("-Ctarget-feature=+crt-static")
+    );
+    assert_eq!(
+        vars.get("CRIT_TEST_OVERRIDE").map(String::as_str),
+        Some("1")
+    );
+    assert!(!vars.contains_key("PATH"));
+    assert!(!vars.contains_key("HOME"));
+}
+
+/// run_logged executes a command, recording its command line, environment, and combined output to a log file.
+///
+/// When stream_prefix is present, output lines are also forwarded live to the console,
+/// each line labeled with the prefix.
+///
+/// Returns the exit status and the captured stderr stream.
+pub fn run_logged(
+    cmd: &mut process::Command,
+    log_path: &path::Path,
+    stream_prefix: Option<&str>,
+) -> Result<(process::ExitStatus, String), CritError> {
+    run_logged_to(cmd, log_path, stream_prefix, io::stdout(), io::stderr())
+}
+
+/// run_logged_to behaves like run_logged, forwarding labeled lines to the given console streams.
+fn run_logged_to<O: Write + Send, E: Write + Send>(
+    cmd: &mut process::Command,
+    log_path: &path::Path,
+    stream_prefix: Option<&str>,
+    stdout_console: O,
+    stderr_console: E,
+) -> Result<(process::ExitStatus, String), CritError> {
+    if let Some(log_dir_path) = log_path.parent() {
+        fs::create_dir_all(log_dir_path)
+            .map_err(|err| CritError::IOError(format!("unable to create log directory: {err}")))?;
+    }
+
+    let mut log_file: fs::File = fs::File::create(log_path)
+        .map_err(|err| CritError::IOError(format!("unable to create log file: {err}")))?;
+
+    let mut header: String = format!("command: {:?}\n", cmd);
+
+    for (key, value) in effective_env(cmd) {
+        header.push_str(&format!("env: {key}={value}\n"));
+    }
+
+    header.push('\n');
+
+    log_file
+        .write_all(header.as_bytes())
+        .map_err(|err| CritError::IOError(format!("unable to write log file: {err}")))?;
+
+    cmd.stdout(process::Stdio::piped());
+    cmd.stderr(process::Stdio::piped());
+
+    let mut child: process::Child = cmd
+        .spawn()
+        .map_err(|err| CritError::IOError(format!("unable to run command: {err}")))?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or(CritError::IOError("unable to capture stdout".to_string()))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or(CritError::IOError("unable to capture stderr".to_string()))?;
+
+    let log: sync::Mutex<fs::File> = sync::Mutex::new(log_file);
+
+    let (stdout_result, stderr_result) = thread::scope(|scope| {
+        let stdout_handle = scope.spawn(|| {
+            copy_lines(
+                stdout,
+                &log,
+                stream_prefix.map(|e| (e, stdout_console)),
+                None,
+            )
+        });
+        let stderr_handle = scope.spawn(|| {
+            let mut captured: Vec<u8> = Vec::new();
+            copy_lines(
+                stderr,
+                &log,
+                stream_prefix.map(|e| (e, stderr_console)),
+                Some(&mut captured),
+            )
+            .map(|_| captured)
+        });
+
+        (stdout_handle.join(), stderr_handle.join())
+    });
+
+    let status: process::ExitStatus = child
+        .wait()
+        .map_err(|err| CritError::IOError(format!("unable to await command: {err}")))?;
+
+    stdout_result.map_err(|_| CritError::IOError("unable to read stdout".to_string()))??;
+    let captured_stderr: Vec<u8> =
+        stderr_result.map_err(|_| CritError::IOError("unable to read stderr".to_string()))??;
+
+    Ok((
+        status,
+        String::from_utf8_lossy(&captured_stderr).to_string(),
+    ))
+}
+
+#[test]
+fn test_run_logged_to() -> Result<(), CritError> {
+    let log_pathbuf: path::PathBuf =
+        env::temp_dir().join(format!("crit-test-run-logged-{}.log", process::id()));
+    let mut cmd: process::Command = process::Command::new("sh");
+    cmd.args(["-c", "echo out; echo err >&2"]);
+    let mut stdout_console: Vec<u8> = Vec::new();
+    let mut stderr_console: Vec<u8> = Vec::new();
+    let (status, captured_stderr) = run_logged_to(
+        &mut cmd,
+        &log_pathbuf,
+        Some("x86_64-unknown-linux-gnu"),
+        &mut stdout_console,
+        &mut stderr_console,
+    )?;
+    let log: String = fs::read_to_string(&log_pathbuf)
+        .map_err(|err| CritError::IOError(format!("unable to read log file: {err}")))?;
+    let _ = fs::remove_file(&log_pathbuf);
+
+    assert!(status.success());
+    assert_eq!(captured_stderr, "err\n");
+    assert_eq!(
+        String::from_utf8_lossy(&stdout_console),
+        "x86_64-unknown-linux-gnu: out\n"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&stderr_console),
+        "x86_64-unknown-linux-gnu: err\n"
+    );
+    assert!(log.starts_with("command: "));
+    assert!(log.lines().any(|e| e == "out"));
+    assert!(log.lines().any(|e| e == "err"));
+    Ok(())
+}
+
+/// copy_lines forwards a command output stream to a shared log file, line by line.
+///
+/// Optionally echoes each line to the console with a label,
+/// writing whole lines at once so that concurrent builds do not interleave mid-line.
+fn copy_lines<R: io::Read, W: Write>(
+    stream: R,
+    log: &sync::Mutex<fs::File>,
+    mut console: Option<(&str, W)>,
+    mut captured: Option<&mut Vec<u8>>,
+) -> Result<(), CritError> {
+    let mut reader = io::BufReader::new(stream);
+    let mut line: Vec<u8> = Vec::new();
+
+    loop {
+        line.clear();
+
+        let n: usize = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|err| CritError::IOError(format!("unable to read command output: {err}")))?;
+
+        if n == 0 {
+            return Ok(());
+        }
+
+        if let Some(buf) = captured.as_mut() {
+            buf.extend_from_slice(&line);
+        }
+
+        if let Some((prefix, console_stream)) = console.as_mut() {
+            let mut labeled_line: Vec<u8> = format!("{prefix}: ").into_bytes();
+            labeled_line.extend_from_slice(&line);
+
+            if !labeled_line.ends_with(b"\n") {
+                labeled_line.push(b'\n');
+            }
+
+            console_stream
+                .write_all(&labeled_line)
+                .map_err(|err| CritError::IOError(format!("unable to write console: {err}")))?;
+        }
+
+        log.lock()
+            .map_err(|err| CritError::IOError(format!("unable to lock log file: {err}")))?
+            .write_all(&line)
+            .map_err(|err| CritError::IOError(format!("unable to write log file: {err}")))?;
+    }
+}
+
+/// Crit models a multiplatform build operation.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Crit {
+    /// debug enables additional logging.
+    pub debug: Option<bool>,
+
+    /// banner denotes an optional parent directory prefix (e.g. "hello-1.0"),
+    /// optionally templated with {name}, {version}, and {git_describe}, or "auto".
+    pub banner: Option<String>,
+
+    /// rustflags maps target triple patterns to custom RUSTFLAGS settings (default: $RUSTFLAGS).
+    pub rustflags: Option<collections::BTreeMap<String, String>>,
+
+    /// feature_excludes skips matching features.
+    pub feature_excludes: Option<Vec<String>>,
+
+    /// packages selects workspace packages to build (default: the current package, or the workspace default members).
+    pub packages: Option<Vec<String>>,
+
+    /// workspace selects all workspace packages.
+    pub workspace: Option<bool>,
+
+    /// collate_packages nests artifacts in per-package subdirectories.
+    pub collate_packages: Option<bool>,
+
+    /// feature_sets builds each target once per feature set (e.g. `[["default"], ["tls-native"]]`).
+    pub feature_sets: Option<Vec<Vec<String>>>,
+
+    /// backend maps target patterns to build backends (default: "cross").
+    pub backend: Option<collections::BTreeMap<String, String>>,
+
+    /// glibc maps target patterns to minimum glibc versions (zigbuild backend only).
+    pub glibc: Option<collections::BTreeMap<String, String>>,
+
+    /// profile selects the cargo build profile, e.g. "dev", "release", or a custom profile (default: "release").
+    pub profile: Option<String>,
+
+    /// cross_args forwards additional flags to the build backend.
+    pub cross_args: Option<Vec<String>>,
+
+    /// dimension_source selects how to read target dimensions, "rustc" or "triple" (default: "rustc").
+    pub dimension_source: Option<String>,
+
+    /// arch collects enabled chipets.
+    #[serde(default)]
+    pub arch: Vec<String>,
+
+    /// vendor collects enabled vendors.
+    #[serde(default)]
+    pub vendor: Vec<String>,
+
+    /// os collects enabled operating systems.
+    #[serde(default)]
+    pub os: Vec<String>,
+
+    /// env collects enabled libc/environment variants (rustc dimension source only).
+    pub env: Option<Vec<String>>,
+
+    /// abi collects enabled chipset/libc variants.
+    #[serde(default)]
+    pub abi: Vec<String>,
+
+    /// include enables targets matching any of the given `cfg(...)` predicates.
+    pub include: Option<Vec<String>>,
+
+    /// exclude skips targets matching any of the given `cfg(...)` predicates.
+    pub exclude: Option<Vec<String>>,
+
+    /// target_excludes skips targets matching the given names, glob patterns, or anchored regex patterns.
+    pub target_excludes: Option<Vec<String>>,
+
+    /// targets enables exactly the named Rust targets, bypassing the dimension lists and include predicates.
+    pub targets: Option<Vec<String>>,
+
+    /// layout denotes the artifact directory template, relative to the bin directory
+    /// (default: the target triple, then any feature set, then any package when collating packages).
+    pub layout: Option<String>,
+
+    /// name denotes the binary file name template, sans extension (default: the binary name).
+    pub name: Option<String>,
+
+    /// arch_aliases overrides the GOARCH style names of rustc arch values, e.g. { "x86" = "x86" }.
+    pub arch_aliases: Option<collections::BTreeMap<String, String>>,
+
+    /// os_aliases overrides the GOOS style names of rustc os values, e.g. { "macos" = "macos" }.
+    pub os_aliases: Option<collections::BTreeMap<String, String>>,
+
+    /// alias_precedence denotes target patterns, from most to least preferred,
+    /// for choosing between targets sharing a platform alias.
+    pub alias_precedence: Option<Vec<String>>,
+
+    /// binary_extensions lists candidate binary file extensions (default: per target platform),
+    /// collating whichever candidates a build produces.
+    pub binary_extensions: Option<Vec<String>>,
+
+    /// universal2 merges the macOS x86_64 and aarch64 artifacts into universal binaries.
+    pub universal2: Option<bool>,
+
+    /// archive packages the collated artifacts of each target into an archive file.
+    pub archive: Option<bool>,
+
+    /// archive_format maps target patterns to archive formats, "tar.gz", "tar.xz", or "zip"
+    /// (default: "zip" for Windows targets, otherwise "tar.gz").
+    pub archive_format: Option<collections::BTreeMap<String, String>>,
+
+    /// archive_name denotes the archive file name template, sans extension (default: `DEFAULT_ARCHIVE_NAME`).
+    pub archive_name: Option<String>,
+
+    /// archive_files collects extra files to bundle into each archive (e.g. README.md, LICENSE).
+    pub archive_files: Option<Vec<String>>,
+
+    /// jobs limits the number of concurrent target builds (default: 1).
+    pub jobs: Option<usize>,
+
+    /// stream forwards live cross output to the console, labeled by target.
+    pub stream: Option<bool>,
+
+    /// keep_going continues building the remaining targets after a target fails.
+    pub keep_going: Option<bool>,
+
+    /// enabled_targets caches enabled Rust targets.
+    #[serde(skip)]
+    enabled_targets: Option<Vec<Target>>,
+
+    /// target_metadata caches rustc metadata for enabled Rust targets.
+    #[serde(skip)]
+    target_metadata: Option<collections::BTreeMap<String, TargetMetadata>>,
+
+    /// platform_aliases caches the platform aliases of enabled Rust targets.
+    #[serde(skip)]
+    platform_aliases: Option<collections::BTreeMap<String, String>>,
+
+    /// enabled_applications caches active applications.
+    #[serde(skip)]
+    enabled_applications: Option<Vec<Application>>,
+
+    /// enabled_packages caches the selected cargo packages.
+    #[serde(skip)]
+    enabled_packages: Option<Vec<CargoPackage>>,
+
+    /// collation_lock serializes artifact copies across concurrent target builds.
+    #[serde(skip)]
+    collation_lock: sync::Mutex<()>,
+
+    /// configuration_path caches the canonical path of the loaded configuration file.
+    #[serde(skip)]
+    configuration_path: Option<path::PathBuf>,
+}
+
+impl Crit {
+    /// read parses a Crit configuration, leaving the targets cache empty.
+    pub fn read(pth: &str) -> Result<Self, CritError> {
+        let toml_string = fs::read_to_string(pth)
+            .map_err(|_| CritError::IOError(format!("unable to read file: {pth}")))?;
+        let mut crit: Crit = toml::from_str(&toml_string)
+            .map_err(|e| CritError::TOMLParseError(e.message().to_string()))?;
+        crit.configuration_path = fs::canonicalize(pth).ok();
+        Ok(crit)
+    }
+
+    /// load generates a Crit.
+    pub fn load(pth: &str) -> Result<Self, CritError> {
+        let mut crit: Crit = Crit::read(pth)?;
+        crit.update_targets()?;
+        Ok(crit)
+    }
+
+    /// update_targets refreshes the targets cache.
+    pub fn update_targets(&mut self) -> Result<(), CritError> {
+        let selections = self
+            .explain_targets()?
+            .into_iter()
+            .filter(|e| e.included)
+            .collect::<Vec<TargetSelection>>();
+
+        let mut unknown_metadata_targets = selections
+            .iter()
+            .filter(|e| e.metadata.is_none())
+            .map(|e| e.triple.clone())
+            .collect::<Vec<String>>();
+
+        if !unknown_metadata_targets.is_empty() {
+            let rustc_targets = rustc_target_list()?;
+            unknown_metadata_targets.retain(|e| rustc_targets.contains(e));
+        }
+
+        let mut target_metadata = query_target_metadata(&unknown_metadata_targets)?;
+
+        for selection in &selections {
+            if let Some(metadata) = &selection.metadata {
+                target_metadata.insert(selection.triple.clone(), metadata.clone());
+            }
+        }
+
+        // Universal binaries follow the naming conventions of their aarch64 slice.
+        if let Some(true) = self.universal2
+            && let Some(metadata) = target_metadata.get(UNIVERSAL2_SOURCE_TARGETS[1])
+        {
+            let universal2_metadata = TargetMetadata {
+                arch: "universal2".to_string(),
+                ..metadata.clone()
+            };
+            target_metadata.insert(UNIVERSAL2_TARGET.to_string(), universal2_metadata);
+        }
+
+        self.enabled_targets = Some(selections.into_iter().map(|e| e.target).collect());
+        self.target_metadata = Some(target_metadata);
+        self.platform_aliases = Some(self.enabled_platform_aliases()?);
+        Ok(())
+    }
+
+    /// metadata retrieves cached rustc metadata for an enabled target.
+    pub fn metadata(&self, target: &str) -> Option<&TargetMetadata> {
+        self.target_metadata.as_ref().and_then(|e| e.get(target))
+    }
+
+    /// available_targets queries rustup for the list of known Rust targets.
+    pub fn available_targets(&self) -> Result<Vec<Target>, CritError> {
+        let mut cmd = process::Command::new("rustup");
+        cmd.args(["target", "list"]);
+
+        if let Some(true) = self.debug {
+            eprintln!("running command: {:?}", cmd);
+        }
+
+        let output = cmd
+            .output()
+            .map_err(|e| CritError::IOError(e.to_string()))?;
+
+        if !output.status.success() {
+            return Err(CritError::IOError(format!(
+                "failed to query rustup targets: {}",
+                output.status
+            )));
+        }
+
+        let stdout_utf8 =
+            String::from_utf8(output.stdout).map_err(|e| CritError::IOError(e.to_string()))?;
+        let mut available_targets: Vec<Target> = Vec::new();
+
+        for line in stdout_utf8.lines() {
+            let target = Target::parse(line)?;
+            available_targets.push(target);
+        }
+
+        Ok(available_targets)
+    }
+
+    /// explain_targets reports whether each available target is enabled, and why.
+    pub fn explain_targets(&self) -> Result<Vec<TargetSelection>, CritError> {
+        let dimension_source =
+            DimensionSource::parse(self.dimension_source.as_deref().unwrap_or("rustc"))?;
+        let available_targets = self.available_targets()?;
+        let mut target_metadata: collections::BTreeMap<String, TargetMetadata> =
+            collections::BTreeMap::new();
+
+        if dimension_source == DimensionSource::Rustc
+            || self.include.iter().flatten().next().is_some()
+            || self.exclude.iter().flatten().next().is_some()
+            || self.targets.iter().flatten().any(|e| e.contains('/'))
+        {
+            let rustc_targets = rustc_target_list()?;
+            let known_targets = available_targets
+                .iter()
+                .map(|e| e.to_string())
+                .filter(|e| rustc_targets.contains(e))
+                .collect::<Vec<String>>();
+            target_metadata = query_target_metadata(&known_targets)?;
+        }
+
+        self.select_targets(available_targets, &target_metadata)
+    }
+
+    /// select_targets reports whether each of the given available targets is enabled, and why,
+    /// consulting the rustc metadata of any targets known to rustc.
+    pub fn select_targets(
+        &self,
+        available_targets: Vec<Target>,
+        target_metadata: &collections::BTreeMap<String, TargetMetadata>,
+    ) -> Result<Vec<TargetSelection>, CritError> {
+        let dimension_source =
+            DimensionSource::parse(self.dimension_source.as_deref().unwrap_or("rustc"))?;
+        let target_exclude_patterns = self
+            .target_excludes
+            .iter()
+            .flatten()
+            .map(|e| compile_target_pattern(e).map(|pattern| (e.clone(), pattern)))
+            .collect::<Result<Vec<(String, regex::Regex)>, CritError>>()?;
+
+        let allowlist_aliases: Vec<&String> = self
+            .targets
+            .iter()
+            .flatten()
+            .filter(|e| e.contains('/'))
+            .collect();
+
+        if let Some(allowlist) = &self.targets {
+            for triple in allowlist.iter().filter(|e| !e.contains('/')) {
+                if !available_targets.iter().any(|e| e.to_string() == *triple) {
+                    return Err(CritError::IOError(format!("invalid target: {triple:?}")));
+                }
+            }
+        }
+
+        let parse_predicates = |e: &Option<Vec<String>>| {
+            e.iter()
+                .flatten()
+                .map(|e2| cfg::Predicate::parse(e2).map(|predicate| (e2.clone(), predicate)))
+                .collect::<Result<Vec<(String, cfg::Predicate)>, CritError>>()
+        };
+        let include_predicates = parse_predicates(&self.include)?;
+        let exclude_predicates = parse_predicates(&self.exclude)?;
+
+        // Resolve GOOS/GOARCH style platform aliases (e.g. "linux/amd64") to target triples.
+        let mut allowlist_alias_triples: collections::BTreeMap<String, String> =
+            collections::BTreeMap::new();
+
+        if !allowlist_aliases.is_empty() {
+            let candidates: Vec<(String, TargetMetadata)> = available_targets
+                .iter()
+                .map(|e| {
+                    let triple: String = e.to_string();
+                    let metadata: TargetMetadata = target_metadata
+                        .get(&triple)
+                        .cloned()
+                        .unwrap_or(alias::triple_metadata(e));
+                    (triple, metadata)
+                })
+                .collect();
+            let winners: collections::BTreeMap<String, String> = self.alias_winners(&candidates)?;
+
+            for platform_alias in allowlist_aliases {
+                let triple: &String =
+                    winners
+                        .get(platform_alias)
+                        .ok_or(CritError::IOError(format!(
+                            "invalid target: {platform_alias:?}"
+                        )))?;
+                allowlist_alias_triples.insert(triple.clone(), platform_alias.clone());
+            }
+        }
+
+        // Normalize each target to (arch, vendor, os, env, abi) dimensions,
+        // where `None` represents an absent dimension.
+        type Dimensions = (
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        );
+
+        let dimensions = |target: &Target| -> Option<Dimensions> {
+            match dimension_source {
+                DimensionSource::Triple => Some((
+                    Some(target.arch.clone()),
+                    Some(target.vendor.clone()),
+                    target.os.clone(),
+                    None,
+                    target.abi.clone(),
+                )),
+                DimensionSource::Rustc => target_metadata.get(&target.to_string()).map(|e| {
+                    (
+                        Some(e.arch.clone()),
+                        Some(e.vendor.clone()),
+                        Some(e.os.clone()),
+                        Some(e.env.clone()),
+                        Some(e.abi.clone()),
+                    )
+                }),
+            }
+        };
+
+        let dimension_set = |values: &[String]| -> collections::HashSet<Option<String>> {
+            values
+                .iter()
+                .map(|e| match (dimension_source, e.as_str()) {
+                    (DimensionSource::Triple, "") => None,
+                    (_, e) => Some(e.to_string()),
+                })
+                .collect()
+        };
+
+        if dimension_source == DimensionSource::Triple && self.env.is_some() {
+            return Err(CritError::IOError(
+                "env requires dimension_source \"rustc\"".to_string(),
+            ));
+        }
+
+        let dimensions_configured: bool = !self.arch.is_empty()
+            || !self.vendor.is_empty()
+            || !self.os.is_empty()
+            || self.env.is_some()
+            || !self.abi.is_empty();
+
+        let arches = dimension_set(&self.arch);
+        let vendors = dimension_set(&self.vendor);
+        let operating_systems = dimension_set(&self.os);
+        let envs = self.env.as_ref().map(|e| dimension_set(e));
+        let abis = dimension_set(&self.abi);
+
+        let target_dimensions = available_targets
+            .iter()
+            .filter_map(&dimensions)
+            .collect::<Vec<Dimensions>>();
+
+        // Rustc cfg values often differ from target identifier components (e.g. "arm" versus "armv7").
+        let invalid_dimension = |dimension: &str, value: &Option<String>| -> CritError {
+            let value_str: &str = value.as_deref().unwrap_or_default();
+
+            match dimension_source {
+                DimensionSource::Rustc => CritError::IOError(format!(
+                    "invalid {dimension}: {value_str:?} is not a rustc target_{dimension} value (to match target identifier components instead, set dimension_source = \"triple\")"
+                )),
+                DimensionSource::Triple => {
+                    CritError::IOError(format!("invalid {dimension}: {value_str:?}"))
+                }
+            }
+        };
+
+        for arch in &arches {
+            if !target_dimensions.iter().any(|e| e.0 == *arch) {
+                return Err(invalid_dimension("arch", arch));
+            }
+        }
+
+        for vendor in &vendors {
+            if !target_dimensions.iter().any(|e| e.1 == *vendor) {
+                return Err(invalid_dimension("vendor", vendor));
+            }
+        }
+
+        for os in &operating_systems {
+            if !target_dimensions.iter().any(|e| e.2 == *os) {
+                return Err(invalid_dimension("os", os));
+            }
+        }
+
+        for env in envs.iter().flatten() {
+            if !target_dimensions.iter().any(|e| e.3 == *env) {
+                return Err(invalid_dimension("env", env));
+            }
+        }
+
+        for abi in &abis {
+            if !target_dimensions.iter().any(|e| e.4 == *abi) {
+                return Err(invalid_dimension("abi", abi));
+            }
+        }
+
+        Ok(available_targets
+            .into_iter()
+            .map(|target| {
+                let triple = target.to_string();
+                let metadata = target_metadata.get(&triple).cloned();
+
+                let cfg: &[(String, Option<String>)] = metadata
+                    .as_ref()
+                    .map(|e| e.cfg.as_slice())
+                    .unwrap_or_default();
+                let included_by: Option<&String> = include_predicates
+                    .iter()
+                    .find(|(_, predicate)| predicate.matches(cfg))
+                    .map(|(source, _)| source);
+                let excluded_by: Option<&String> = exclude_predicates
+                    .iter()
+                    .find(|(_, predicate)| predicate.matches(cfg))
+                    .map(|(source, _)| source);
+
+                // Enablement models whether the target is enabled, with a reason.
+                let enablement: Result<String, String> = if let Some(allowlist) = &self.targets {
+                    if allowlist.contains(&triple) {
+                        Ok("included by targets".to_string())
+                    } else if let Some(platform_alias) = allowlist_alias_triples.get(&triple) {
+                        Ok(format!("included by targets as {platform_alias}"))
+                    } else {
+                        Err("excluded: not listed in targets".to_string())
+                    }
+                } else {
+                    let dimension_exclusion: Option<String> = match dimensions(&target) {
+                        None => Some("excluded: unknown to rustc".to_string()),
+                        Some(_) if !dimensions_configured && !include_predicates.is_empty() => {
+                            Some("excluded: no include predicate matched".to_string())
+                        }
+                        Some((arch, vendor, os, env, abi)) => {
+                            if !arches.contains(&arch) {
+                                Some(format!(
+                                    "excluded: arch '{}' not enabled",
+                                    arch.unwrap_or_default()
+                                ))
+                            } else if !vendors.contains(&vendor) {
+                                Some(format!(
+                                    "excluded: vendor '{}' not enabled",
+                                    vendor.unwrap_or_default()
+                                ))
+                            } else if !operating_systems.contains(&os) {
+                                Some(format!(
+                                    "excluded: os '{}' not enabled",
+                                    os.unwrap_or_default()
+                                ))
+                            } else if let Some(envs) = &envs
+                                && !envs.contains(&env)
+                            {
+                                Some(format!(
+                                    "excluded: env '{}' not enabled",
+                                    env.unwrap_or_default()
+                                ))
+                            } else if !abis.contains(&abi) {
+                                Some(format!(
+                                    "excluded: abi '{}' not enabled",
+                                    abi.unwrap_or_default()
+                                ))
+                            } else {
+                                None
+                            }
+                        }
+                    };
+
+                    match (dimension_exclusion, included_by) {
+                        (None, _) => Ok("included".to_string()),
+                        (Some(_), Some(e)) => Ok(format!("included by include '{e}'")),
+                        (Some(e), None) => Err(e),
+                    }
+                };
+
+                let exclusion: Option<String> = if let Err(e) = &enablement {
+                    Some(e.clone())
+                } else if let Some(e) = excluded_by {
+                    Some(format!("excluded by exclude '{e}'"))
+                } else if let Some(e) = target_exclude_patterns
+                    .iter()
+                    .find(|(_, pattern)| pattern.is_match(&triple))
+                    .map(|(source, _)| source)
+                {
+                    if e == &triple {
+                        Some("excluded by target_excludes".to_string())
+                    } else {
+                        Some(format!("excluded by target_excludes '{e}'"))
+                    }
+                } else {
+                    None
+                };
+
+                TargetSelection {
+                    triple,
+                    target,
+                    metadata,
+                    included: exclusion.is_none(),
+                    reason: exclusion.unwrap_or(enablement.unwrap_or_default()),
+                }
+            })
+            .collect())
+    }
+
+    /// target_rustflags merges $RUSTFLAGS with every rustflags entry matching a target.
+    pub fn target_rustflags(&self, target: &str) -> Result<String, CritError> {
+        let mut rustflags: Vec<String> = match env::var("RUSTFLAGS") {
+            Ok(e) => vec![e],
+            _ => Vec::new(),
+        };
+
+        for (target_pattern_string, rf) in self.rustflags.iter().flatten() {
+            if compile_target_pattern(target_pattern_string)?.is_match(target) {
+                rustflags.push(rf.clone());
+            }
+        }
+
+        Ok(rustflags.join(" "))
+    }
+
+    /// target_backend resolves the build backend for a target.
+    pub fn target_backend(&self, target: &str) -> Result<Box<dyn backend::Backend>, CritError> {
+        let backend_name: &str = match_target_patterns(self.backend.as_ref(), target, "backends")?
+            .unwrap_or(backend::DEFAULT_BACKEND);
+        let glibc_version: Option<String> =
+            match_target_patterns(self.glibc.as_ref(), target, "glibc versions")?
+                .map(|e| e.to_string());
+        backend::lookup(backend_name, &backend::BackendOptions { glibc_version })
+    }
+
+    /// target_archive_format resolves the archive format for a target.
+    pub fn target_archive_format(&self, target: &str) -> Result<archive::ArchiveFormat, CritError> {
+        match match_target_patterns(self.archive_format.as_ref(), target, "archive formats")? {
+            Some(name) => archive::ArchiveFormat::parse(name),
+            None if self.metadata(target).map(|e| e.os.as_str()) == Some("windows") => {
+                Ok(archive::ArchiveFormat::Zip)
+            }
+            None => Ok(archive::ArchiveFormat::TarGz),
+        }
+    }
+
+    /// archive_stem renders the archive name for a target, sans extension.
+    ///
+    /// The name and version placeholders refer to the first selected package.
+    pub fn archive_stem(&self, target: &str) -> Result<String, CritError> {
+        let package: &CargoPackage = self
+            .enabled_packages
+            .as_deref()
+            .unwrap_or_default()
+            .first()
+            .ok_or(CritError::IOError("no packages selected".to_string()))?;
+        let values: collections::BTreeMap<&str, String> = self.target_values(target, package)?;
+
+        render_filename(
+            self.archive_name.as_deref().unwrap_or(DEFAULT_ARCHIVE_NAME),
+            &values,
+        )
+    }
+
+    /// archive_path resolves the archive file location for a target.
+    pub fn archive_path(
+        &self,
+        target: &str,
+        bin_dir_path: &path::Path,
+    ) -> Result<path::PathBuf, CritError> {
+        Ok(bin_dir_path.join(format!(
+            "{}.{}",
+            self.archive_stem(target)?,
+            self.target_archive_format(target)?
+        )))
+    }
+
+    /// archive_target packages the collated artifacts of a target.
+    pub fn archive_target(
+        &self,
+        target: &str,
+        bin_dir_path: &path::Path,
+    ) -> Result<path::PathBuf, CritError> {
+        let archive_pathbuf: path::PathBuf = self.archive_path(target, bin_dir_path)?;
+        let mut dest_pathbufs: Vec<path::PathBuf> = Vec::new();
+
+        for (_, feature_set) in self.build_matrix()?.iter().filter(|(e, _)| e == target) {
+            dest_pathbufs.extend(self.collated_destinations(
+                target,
+                feature_set.as_deref(),
+                bin_dir_path,
+            )?);
+        }
+
+        // Nest artifacts relative to their deepest common directory.
+        let mut base_pathbuf: path::PathBuf = dest_pathbufs
+            .first()
+            .and_then(|e| e.parent())
+            .map(|e| e.to_path_buf())
+            .unwrap_or(bin_dir_path.to_path_buf());
+
+        while !dest_pathbufs.iter().all(|e| e.starts_with(&base_pathbuf)) {
+            match base_pathbuf.parent() {
+                Some(parent) => base_pathbuf = parent.to_path_buf(),
+                None => break,
+            }
+        }
+
+        let mut files: Vec<(String, path::PathBuf)> = Vec::new();
+
+        for dest_pathbuf in dest_pathbufs {
+            files.push((
+                checksum::relative_path(&base_pathbuf, &dest_pathbuf)?,
+                dest_pathbuf,
+            ));
+        }
+
+        let extra_files: Vec<path::PathBuf> = self
+            .archive_files
+            .iter()
+            .flatten()
+            .map(path::PathBuf::from)
+            .collect();
+        let entries: Vec<archive::Entry> =
+            archive::collect_entries(&self.archive_stem(target)?, &files, &extra_files)?;
+
+        archive::write_archive(
+            self.target_archive_format(target)?,
+            &archive_pathbuf,
+            &entries,
+        )?;
+        Ok(archive_pathbuf)
+    }
+
+    /// build_matrix pairs each enabled target with each feature set.
+    pub fn build_matrix(&self) -> Result<Vec<Build>, CritError> {
+        let targets: Vec<String> = self
+            .enabled_targets
+            .clone()
+            .unwrap_or_default()
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+
+        match &self.feature_sets {
+            None => Ok(targets.into_iter().map(|e| (e, None)).collect()),
+            Some(feature_sets) if feature_sets.is_empty() => Err(CritError::IOError(
+                "feature_sets requires at least one feature set".to_string(),
+            )),
+            Some(feature_sets) => Ok(targets
+                .iter()
+                .flat_map(|target| {
+                    feature_sets
+                        .iter()
+                        .map(|feature_set| (target.clone(), Some(feature_set.clone())))
+                })
+                .collect()),
+        }
+    }
+
+    /// build_label identifies a target build in logs and reports.
+    pub fn build_label(target: &str, feature_set: Option<&[String]>) -> String {
+        match feature_set {
+            Some(feature_set) => format!("{target} [{}]", feature_set_label(feature_set)),
+            None => target.to_string(),
+        }
+    }
+
+    /// target_dir resolves the cargo target directory for a target build.
+    pub fn target_dir(target: &str, feature_set: Option<&[String]>) -> path::PathBuf {
+        match feature_set {
+            Some(feature_set) => CROSS_DIR_PATHBUF
+                .join(target)
+                .join(feature_set_label(feature_set)),
+            None => CROSS_DIR_PATHBUF.join(target),
+        }
+    }
+
+    /// log_path resolves the build log location for a target build.
+    pub fn log_path(target: &str, feature_set: Option<&[String]>) -> path::PathBuf {
+        match feature_set {
+            Some(feature_set) => {
+                LOGS_DIR_PATHBUF.join(format!("{target}-{}.log", feature_set_label(feature_set)))
+            }
+            None => LOGS_DIR_PATHBUF.join(format!("{target}.log")),
+        }
+    }
+
+    /// feature_set_applications lists the enabled applications that a build produces.
+    ///
+    /// Without a feature set, every application builds, with its required features enabled.
+    /// Within a feature set, cargo skips binaries whose required features the set does not enable.
+    pub fn feature_set_applications(&self, feature_set: Option<&[String]>) -> Vec<Application> {
+        let applications: Vec<Application> = self.enabled_applications.clone().unwrap_or_default();
+
+        let Some(feature_set) = feature_set else {
+            return applications;
+        };
+
+        let packages: &[CargoPackage] = self.enabled_packages.as_deref().unwrap_or_default();
+
+        applications
+            .into_iter()
+            .filter(|application| {
+                let features: collections::BTreeSet<String> = packages
+                    .iter()
+                    .find(|e| e.name == application.package)
+                    .map(|e| enabled_features(e, feature_set))
+                    .unwrap_or_else(|| feature_set.iter().cloned().collect());
+
+                application
+                    .required_features
+                    .iter()
+                    .all(|e| features.contains(e))
+            })
+            .collect()
+    }
+
+    /// feature_args generates cargo feature flags,
+    /// enabling the required features of the built applications along with any feature set.
+    ///
+    /// Features are qualified by package name, as cargo requires in workspaces.
+    pub fn feature_args(&self, feature_set: Option<&[String]>) -> Result<Vec<String>, CritError> {
+        let mut args: Vec<String> = Vec::new();
+        let mut features: collections::BTreeSet<String> = collections::BTreeSet::new();
+
+        for application in self.feature_set_applications(feature_set) {
+            for feature in &application.required_features {
+                features.insert(format!("{}/{feature}", application.package));
+            }
+        }
+
+        if let Some(feature_set) = feature_set {
+            if !feature_set.iter().any(|e| e == "default") {
+                args.push("--no-default-features".to_string());
+            }
+
+            let packages: &[CargoPackage] = self.enabled_packages.as_deref().unwrap_or_default();
+
+            for feature in feature_set.iter().filter(|e| *e != "default") {
+                let declaring_packages: Vec<&CargoPackage> = packages
+                    .iter()
+                    .filter(|e| e.features.contains_key(feature))
+                    .collect();
+
+                if declaring_packages.is_empty() {
+                    return Err(CritError::IOError(format!("unknown feature: {feature}")));
+                }
+
+                for package in declaring_packages {
+                    features.insert(format!("{}/{feature}", package.name));
+                }
+            }
+        }
+
+        if !features.is_empty() {
+            args.push("--features".to_string());
+            args.push(features.into_iter().collect::<Vec<String>>().join(","));
+        }
+
+        Ok(args)
+    }
+
+    /// build_command prepares a build for a target.
+    pub fn build_command(
+        &self,
+        target: &str,
+        feature_set: Option<&[String]>,
+    ) -> Result<process::Command, CritError> {
+        let target_dir_pathbuf = &Crit::target_dir(target, feature_set);
+        let target_dir_str: &str = &target_dir_pathbuf.display().to_string();
+        let base_args = [
+            "build",
+            "--target-dir",
+            target_dir_str,
+            "--target",
+            target,
+            "--profile",
+            &self.profile_or_default(),
+        ]
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<String>>();
+        let package_args: Vec<String> = if let Some(packages) = &self.packages {
+            packages
+                .iter()
+                .flat_map(|e| ["-p".to_string(), e.clone()])
+                .collect()
+        } else if let Some(true) = self.workspace {
+            vec!["--workspace".to_string()]
+        } else {
+            Vec::new()
+        };
+        let feature_args: Vec<String> = self.feature_args(feature_set)?;
+        let extra_args = self.cross_args.clone().unwrap_or_default();
+        let args = [base_args, package_args, feature_args, extra_args].concat();
+
+        let mut cmd = self.target_backend(target)?.command(target, &args);
+        cmd.env("RUSTFLAGS", self.target_rustflags(target)?);
+        Ok(cmd)
+    }
+
+    /// profile_or_default resolves the cargo build profile.
+    pub fn profile_or_default(&self) -> String {
+        self.profile.clone().unwrap_or("release".to_string())
+    }
+
+    /// profile_dir names the cargo output directory of the build profile.
+    ///
+    /// Cargo places the built-in dev and test profiles in `debug`,
+    /// the release and bench profiles in `release`,
+    /// and custom profiles in a directory named after the profile.
+    pub fn profile_dir(&self) -> String {
+        match self.profile_or_default().as_str() {
+            "dev" | "test" => "debug".to_string(),
+            "bench" => "release".to_string(),
+            profile => profile.to_string(),
+        }
+    }
+
+    /// artifact_filenames lists the expected artifact file names of an application for a target.
+    pub fn artifact_filenames(
+        &self,
+        target: &str,
+        application: &Application,
+    ) -> Result<Vec<String>, CritError> {
+        if application.is_binary()
+            && let Some(binary_extensions) = &self.binary_extensions
+        {
+            return Ok(binary_extensions
+                .iter()
+                .map(|extension| {
+                    let mut pathbuf: path::PathBuf = path::PathBuf::from(&application.name);
+                    pathbuf.set_extension(extension);
+                    pathbuf.display().to_string()
+                })
+                .collect());
+        }
+
+        let metadata: &TargetMetadata = self.metadata(target).ok_or(CritError::IOError(
+            format!("missing rustc metadata for target: {target}"),
+        ))?;
+
+        if application.is_binary() {
+            return Ok(metadata.binary_filenames(&application.name));
+        }
+
+        Ok(application
+            .crate_types
+            .iter()
+            .flat_map(|e| metadata.library_filenames(&application.name, e))
+            .collect())
+    }
+
+    /// arch_alias names the chipset of a target, honoring any arch_aliases override.
+    pub fn arch_alias(&self, metadata: &TargetMetadata) -> String {
+        self.arch_aliases
+            .as_ref()
+            .and_then(|e| e.get(&metadata.arch))
+            .cloned()
+            .unwrap_or(alias::arch_alias(metadata))
+    }
+
+    /// os_alias names the operating system of a target, honoring any os_aliases override.
+    pub fn os_alias(&self, metadata: &TargetMetadata) -> String {
+        self.os_aliases
+            .as_ref()
+            .and_then(|e| e.get(&metadata.os))
+            .cloned()
+            .unwrap_or(alias::os_alias(metadata))
+    }
+
+    /// platform_alias names a target in GOOS/GOARCH style, e.g. "linux/amd64".
+    pub fn platform_alias(&self, metadata: &TargetMetadata) -> String {
+        format!("{}/{}", self.os_alias(metadata), self.arch_alias(metadata))
+    }
+
+    /// alias_winners chooses the most preferred target for each platform alias,
+    /// according to alias_precedence. Ties favor the lexically least target.
+    pub fn alias_winners(
+        &self,
+        candidates: &[(String, TargetMetadata)],
+    ) -> Result<collections::BTreeMap<String, String>, CritError> {
+        let precedence_patterns: Vec<regex::Regex> = match &self.alias_precedence {
+            Some(patterns) => patterns
+                .iter()
+                .map(|e| compile_target_pattern(e))
+                .collect::<Result<Vec<regex::Regex>, CritError>>()?,
+            None => alias::DEFAULT_ALIAS_PRECEDENCE
+                .iter()
+                .map(|e| compile_target_pattern(e))
+                .collect::<Result<Vec<regex::Regex>, CritError>>()?,
+        };
+        let rank = |triple: &str| -> (usize, String) {
+            (
+                precedence_patterns
+                    .iter()
+                    .position(|e| e.is_match(triple))
+                    .unwrap_or(precedence_patterns.len()),
+                triple.to_string(),
+            )
+        };
+        let mut winners: collections::BTreeMap<String, String> = collections::BTreeMap::new();
+
+        for (triple, metadata) in candidates {
+            let platform_alias: String = self.platform_alias(metadata);
+
+            match winners.get(&platform_alias) {
+                Some(winner) if rank(winner) <= rank(triple) => {}
+                _ => {
+                    winners.insert(platform_alias, triple.clone());
+                }
+            }
+        }
+
+        Ok(winners)
+    }
+
+    /// enabled_platform_aliases maps each enabled target to its platform alias,
+    /// omitting targets outranked by another enabled target with the same alias.
+    pub fn enabled_platform_aliases(
+        &self,
+    ) -> Result<collections::BTreeMap<String, String>, CritError> {
+        let candidates: Vec<(String, TargetMetadata)> = self
+            .enabled_targets
+            .iter()
+            .flatten()
+            .map(|e| {
+                let triple: String = e.to_string();
+                let metadata: TargetMetadata = self
+                    .metadata(&triple)
+                    .cloned()
+                    .unwrap_or(alias::triple_metadata(e));
+                (triple, metadata)
+            })
+            .collect();
+
+        Ok(self
+            .alias_winners(&candidates)?
+            .into_iter()
+            .map(|(platform_alias, triple)| (triple, platform_alias))
+            .collect())
+    }
+
+    /// platform_aliases retrieves the cached platform aliases of the enabled targets,
+    /// computing them for uncached configurations.
+    pub fn platform_aliases(&self) -> Result<collections::BTreeMap<String, String>, CritError> {
+        match &self.platform_aliases {
+            Some(platform_aliases) => Ok(platform_aliases.clone()),
+            None => self.enabled_platform_aliases(),
+        }
+    }
+
+    /// target_values resolves the template placeholders of a target, for a package.
+    pub fn target_values(
+        &self,
+        target: &str,
+        package: &CargoPackage,
+    ) -> Result<collections::BTreeMap<&'static str, String>, CritError> {
+        let parsed_target: Target = Target::parse(target)?;
+        // Prefer rustc metadata, approximating it from the triple for targets unknown to rustc.
+        let metadata: TargetMetadata = self
+            .metadata(target)
+            .cloned()
+            .unwrap_or(alias::triple_metadata(&parsed_target));
+        let platform_alias: String = self
+            .platform_aliases()?
+            .remove(target)
+            .unwrap_or(target.to_string());
+
+        Ok([
+            ("name", package.name.clone()),
+            ("version", package.version.clone()),
+            ("target", target.to_string()),
+            ("arch", parsed_target.arch.clone()),
+            ("vendor", parsed_target.vendor.clone()),
+            ("os", parsed_target.os.clone().unwrap_or_default()),
+            ("abi", parsed_target.abi.clone().unwrap_or_default()),
+            ("target_arch", metadata.arch.clone()),
+            ("target_vendor", metadata.vendor.clone()),
+            ("target_os", metadata.os.clone()),
+            ("target_env", metadata.env.clone()),
+            ("target_abi", metadata.abi.clone()),
+            ("arch_alias", self.arch_alias(&metadata)),
+            ("os_alias", self.os_alias(&metadata)),
+            ("alias", platform_alias),
+        ]
+        .into_iter()
+        .collect())
+    }
+
+    /// application_values resolves the template placeholders of an application build.
+    pub fn application_values(
+        &self,
+        target: &str,
+        feature_set: Option<&[String]>,
+        application: &Application,
+    ) -> Result<collections::BTreeMap<&'static str, String>, CritError> {
+        let package: CargoPackage = self
+            .enabled_packages
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .find(|e| e.name == application.package)
+            .cloned()
+            .unwrap_or(CargoPackage {
+                name: application.package.clone(),
+                ..Default::default()
+            });
+        let mut values = self.target_values(target, &package)?;
+        values.insert("bin", application.name.clone());
+        values.insert(
+            "feature_set",
+            feature_set.map(feature_set_label).unwrap_or_default(),
+        );
+        Ok(values)
+    }
+
+    /// artifact_pairs lists the cargo output file names and collation paths of a build.
+    pub fn artifact_pairs(
+        &self,
+        target: &str,
+        feature_set: Option<&[String]>,
+        bin_dir_path: &path::Path,
+    ) -> Result<Vec<(String, path::PathBuf)>, CritError> {
+        let mut pairs: Vec<(String, path::PathBuf)> = Vec::new();
+        let emscripten: bool = self.metadata(target).is_some_and(|e| e.os == "emscripten");
+
+        for application in self.feature_set_applications(feature_set) {
+            let dest_dir_pathbuf: path::PathBuf =
+                self.application_dir(target, feature_set, bin_dir_path, &application)?;
+
+            for filename in self.artifact_filenames(target, &application)? {
+                // Binary names take the name template, keeping the platform file extension.
+                // Emscripten loaders refer to their .wasm module by its original name.
+                let dest_filename: String = match &self.name {
+                    Some(_) if filename.ends_with(".wasm") && emscripten => filename.clone(),
+                    Some(name_template) if application.is_binary() => format!(
+                        "{}{}",
+                        render_filename(
+                            name_template,
+                            &self.application_values(target, feature_set, &application)?
+                        )?,
+                        &filename[application.name.len()..]
+                    ),
+                    _ => filename.clone(),
+                };
+
+                pairs.push((filename, dest_dir_pathbuf.join(dest_filename)));
+            }
+        }
+
+        Ok(pairs)
+    }
+
+    /// artifact_destinations lists the collation paths of a build.
+    pub fn artifact_destinations(
+        &self,
+        target: &str,
+        feature_set: Option<&[String]>,
+        bin_dir_path: &path::Path,
+    ) -> Result<Vec<path::PathBuf>, CritError> {
+        Ok(self
+            .artifact_pairs(target, feature_set, bin_dir_path)?
+            .into_iter()
+            .map(|(_, e)| e)
+            .collect())
+    }
+
+    /// collated_destinations lists the collation paths of a completed build,
+    /// omitting any binary_extensions candidates that the build did not produce.
+    pub fn collated_destinations(
+        &self,
+        target: &str,
+        feature_set: Option<&[String]>,
+        bin_dir_path: &path::Path,
+    ) -> Result<Vec<path::PathBuf>, CritError> {
+        let mut dest_pathbufs: Vec<path::PathBuf> =
+            self.artifact_destinations(target, feature_set, bin_dir_path)?;
+
+        if self.binary_extensions.is_some() {
+            dest_pathbufs.retain(|e| e.exists());
+        }
+
+        Ok(dest_pathbufs)
+    }
+
+    /// universal2_feature_sets lists the feature sets of universal binaries,
+    /// requiring builds of every UNIVERSAL2_SOURCE_TARGETS target.
+    pub fn universal2_feature_sets(&self) -> Result<Vec<Option<Vec<String>>>, CritError> {
+        if self.universal2 != Some(true) {
+            return Ok(Vec::new());
+        }
+
+        let builds: Vec<Build> = self.build_matrix()?;
+
+        for source_target in UNIVERSAL2_SOURCE_TARGETS {
+            if !builds.iter().any(|(e, _)| e == source_target) {
+                return Err(CritError::IOError(format!(
+                    "universal2 requires target {source_target}"
+                )));
+            }
+        }
+
+        Ok(builds
+            .into_iter()
+            .filter(|(e, _)| e == UNIVERSAL2_SOURCE_TARGETS[1])
+            .map(|(_, feature_set)| feature_set)
+            .collect())
+    }
+
+    /// universal2_sources pairs each universal binary path with its (rustc arch, source path) slices,
+    /// in UNIVERSAL2_SOURCE_TARGETS order.
+    ///
+    /// Slices pair by cargo output file name, as name templates may render distinct destination names per target.
+    pub fn universal2_sources(
+        &self,
+        feature_set: Option<&[String]>,
+        bin_dir_path: &path::Path,
+    ) -> Result<Vec<UniversalBinary>, CritError> {
+        let mut slices: collections::BTreeMap<String, Vec<(String, path::PathBuf)>> =
+            collections::BTreeMap::new();
+
+        for source_target in UNIVERSAL2_SOURCE_TARGETS {
+            let arch: String = Target::parse(source_target)?.arch;
+
+            for (filename, dest_pathbuf) in
+                self.artifact_pairs(source_target, feature_set, bin_dir_path)?
+            {
+                slices
+                    .entry(filename)
+                    .or_default()
+                    .push((arch.clone(), dest_pathbuf));
+            }
+        }
+
+        let mut universal_binaries: Vec<UniversalBinary> = Vec::new();
+
+        for (filename, dest_pathbuf) in
+            self.artifact_pairs(UNIVERSAL2_TARGET, feature_set, bin_dir_path)?
+        {
+            match slices.remove(&filename) {
+                Some(sources) if sources.len() == UNIVERSAL2_SOURCE_TARGETS.len() => {
+                    universal_binaries.push((dest_pathbuf, sources));
+                }
+                _ => {
+                    return Err(CritError::IOError(format!(
+                        "universal2: {filename} lacks a slice from each of {UNIVERSAL2_SOURCE_TARGETS:?}"
+                    )));
+                }
+            }
+        }
+
+        if let Some(filename) = slices.keys().next() {
+            return Err(CritError::IOError(format!(
+                "universal2: {filename} lacks a universal binary"
+            )));
+        }
+
+        Ok(universal_binaries)
+    }
+
+    /// build_universal2 merges collated macOS artifacts into universal binaries,
+    /// verifying each slice along with the merged file.
+    pub fn build_universal2(
+        &self,
+        feature_set: Option<&[String]>,
+        bin_dir_path: &path::Path,
+    ) -> Result<Vec<path::PathBuf>, CritError> {
+        let mut dest_pathbufs: Vec<path::PathBuf> = Vec::new();
+
+        for (dest_pathbuf, sources) in self.universal2_sources(feature_set, bin_dir_path)? {
+            // binary_extensions candidates that neither slice build produced have no universal binary.
+            if self.binary_extensions.is_some() && sources.iter().all(|(_, e)| !e.exists()) {
+                continue;
+            }
+
+            if let Some(dest_dir_path) = dest_pathbuf.parent() {
+                fs::create_dir_all(dest_dir_path)
+                    .map_err(|err| CritError::IOError(err.to_string()))?;
+            }
+
+            for ((_, source_pathbuf), source_target) in
+                sources.iter().zip(UNIVERSAL2_SOURCE_TARGETS)
+            {
+                if let Some(metadata) = self.metadata(source_target) {
+                    object::verify(source_pathbuf, metadata)?;
+                }
+            }
+
+            macho::write_universal(&dest_pathbuf, &sources)?;
+
+            if let Some(metadata) = self.metadata(UNIVERSAL2_TARGET)
+                && let Err(err) = object::verify(&dest_pathbuf, metadata)
+            {
+                let _ = fs::remove_file(&dest_pathbuf);
+                return Err(err);
+            }
+
+            dest_pathbufs.push(dest_pathbuf);
+        }
+
+        Ok(dest_pathbufs)
+    }
+
+    /// check_collisions reports collation paths and archives shared by distinct artifacts.
+    pub fn check_collisions(&self, bin_dir_path: &path::Path) -> Result<(), CritError> {
+        let mut owners: collections::BTreeMap<path::PathBuf, String> = collections::BTreeMap::new();
+        let mut collisions: Vec<CritError> = Vec::new();
+        let mut claim = |dest_pathbuf: path::PathBuf, owner: String| match owners.get(&dest_pathbuf)
+        {
+            Some(previous_owner) if *previous_owner != owner => {
+                collisions.push(CritError::IOError(format!(
+                    "collision: {} is produced by both {previous_owner} and {owner}",
+                    dest_pathbuf.display()
+                )));
+            }
+            _ => {
+                owners.insert(dest_pathbuf, owner);
+            }
+        };
+
+        for (target, feature_set) in self.build_matrix()? {
+            let feature_set: Option<&[String]> = feature_set.as_deref();
+
+            for (filename, dest_pathbuf) in
+                self.artifact_pairs(&target, feature_set, bin_dir_path)?
+            {
+                claim(
+                    dest_pathbuf,
+                    format!("{filename} ({})", Crit::build_label(&target, feature_set)),
+                );
+            }
+        }
+
+        for feature_set in self.universal2_feature_sets()? {
+            let feature_set: Option<&[String]> = feature_set.as_deref();
+
+            for dest_pathbuf in
+                self.artifact_destinations(UNIVERSAL2_TARGET, feature_set, bin_dir_path)?
+            {
+                claim(
+                    dest_pathbuf,
+                    Crit::build_label(UNIVERSAL2_TARGET, feature_set),
+                );
+            }
+        }
+
+        if let Some(true) = self.archive {
+            for target in self.enabled_targets.clone().unwrap_or_default() {
+                let target_str: &str = &target.to_string();
+                claim(
+                    self.archive_path(target_str, bin_dir_path)?,
+                    format!("the {target_str} archive"),
+                );
+            }
+        }
+
+        match collisions.len() {
+            0 => Ok(()),
+            1 => Err(collisions.remove(0)),
+            _ => Err(CritError::TargetErrors(collisions)),
+        }
+    }
+
+    /// build_target executes a target build.
+    pub fn build_target(
+        &self,
+        target: &str,
+        feature_set: Option<&[String]>,
+        bin_dir_path: &path::Path,
+    ) -> Result<(), CritError> {
+        let target_dir_pathbuf = &Crit::target_dir(target, feature_set);
+        self.target_backend(target)?
+            .prepare(target, self.debug == Some(true))?;
+        let mut cmd = self.build_command(target, feature_set)?;
+
+        if let Some(true) = self.debug {
+            eprintln!("debug: running command: {:?}", cmd);
+        }
+
+        let build_label: String = Crit::build_label(target, feature_set);
+        let log_pathbuf: path::PathBuf = Crit::log_path(target, feature_set);
+        let stream_prefix: Option<&str> = match self.stream {
+            Some(true) => Some(&build_label),
+            _ => None,
+        };
+        let (build_status, build_stderr) = run_logged(&mut cmd, &log_pathbuf, stream_prefix)?;
+
+        if !build_status.success() {
+            eprintln!("error: {build_label} build log: {}", log_pathbuf.display());
+            return Err(CritError::IOError(format!(
+                "{build_stderr}\nlog: {}",
+                log_pathbuf.display()
+            )));
+        }
+
+        let profile_dir_pathbuf: path::PathBuf =
+            target_dir_pathbuf.join(target).join(self.profile_dir());
+
+        // binary_extensions lists candidate files, requiring at least one per binary.
+        let mut absent_filenames: collections::HashSet<String> = collections::HashSet::new();
+
+        if let Some(binary_extensions) = &self.binary_extensions {
+            for application in self
+                .feature_set_applications(feature_set)
+                .iter()
+                .filter(|e| e.is_binary())
+            {
+                let filenames: Vec<String> = self.artifact_filenames(target, application)?;
+                let absent: Vec<String> = filenames
+                    .iter()
+                    .filter(|e| !profile_dir_pathbuf.join(e).exists())
+                    .cloned()
+                    .collect();
+
+                if absent.len() == filenames.len() {
+                    return Err(CritError::IOError(format!(
+                        "missing artifact: {} (binary_extensions: {binary_extensions:?})",
+                        profile_dir_pathbuf.join(&application.name).display()
+                    )));
+                }
+
+                absent_filenames.extend(absent);
+            }
+        }
+
+        let _collation_guard = self.collation_lock.lock().map_err(|err| {
+            CritError::IOError(format!("unable to lock artifact collation: {err}"))
+        })?;
+
+        for (filename, dest_pathbuf) in self.artifact_pairs(target, feature_set, bin_dir_path)? {
+            if absent_filenames.contains(&filename) {
+                continue;
+            }
+
+            let source_pathbuf: path::PathBuf = profile_dir_pathbuf.join(&filename);
+            let source_str: &str = &source_pathbuf.display().to_string();
+
+            if !source_pathbuf.exists() {
+                return Err(CritError::IOError(format!(
+                    "missing artifact: {source_str}"
+                )));
+            }
+
+            // Verify before collating, so that mismatched artifacts never reach the bin directory.
+            if let Some(metadata) = self.metadata(target) {
+                object::verify(&source_pathbuf, metadata)?;
+            }
+
+            if let Some(dest_dir_path) = dest_pathbuf.parent() {
+                fs::create_dir_all(dest_dir_path)
+                    .map_err(|err| CritError::IOError(err.to_string()))?;
+            }
+
+            let dest_str: &str = &dest_pathbuf.display().to_string();
+
+            fs::copy(source_str, dest_str).map_err(|err| CritError::IOError(err.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// selected_packages resolves the workspace packages to build.
+    pub fn selected_packages<'a>(
+        &self,
+        metadata: &'a CargoMetadata,
+    ) -> Result<Vec<&'a CargoPackage>, CritError> {
+        if let Some(names) = &self.packages {
+            return names.iter().map(|e| metadata.package(e)).collect();
+        }
+
+        if let Some(true) = self.workspace {
+            return Ok(metadata.members(&metadata.workspace_members));
+        }
+
+        match metadata.current_package()? {
+            Some(package) => Ok(vec![package]),
+            None => Ok(metadata.members(&metadata.workspace_default_members)),
+        }
+    }
+
+    /// package_feature_excludes resolves feature_excludes for a package,
+    /// preferring any feature_excludes in a crit.toml alongside the package Cargo.toml.
+    pub fn package_feature_excludes(
+        &self,
+        package: &CargoPackage,
+    ) -> Result<Vec<String>, CritError> {
+        if let Some(feature_excludes) = package
+            .member_configuration(self.configuration_path.as_deref())?
+            .and_then(|e| e.feature_excludes)
+        {
+            return Ok(feature_excludes);
+        }
+
+        Ok(self.feature_excludes.clone().unwrap_or_default())
+    }
+
+    /// update_applications refreshes the enabled applications cache.
+    pub fn update_applications(&mut self) -> Result<(), CritError> {
+        let metadata = CargoMetadata::query()?;
+        let packages: Vec<&CargoPackage> = self.selected_packages(&metadata)?;
+        let mut applications: Vec<Application> = Vec::new();
+
+        for package in &packages {
+            let feature_excludes_strings = self.package_feature_excludes(package)?;
+            let feature_excludes_strs = feature_excludes_strings
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<&str>>();
+            applications.extend(get_applications(package, &feature_excludes_strs));
+        }
+
+        if applications.is_empty() {
+            return Err(CritError::IOError(
+                "no binaries or C-compatible libraries found".to_string(),
+            ));
+        }
+
+        self.enabled_applications = Some(applications);
+        self.enabled_packages = Some(packages.into_iter().cloned().collect());
+        Ok(())
+    }
+
+    /// resolve_banner renders any banner template against the first selected package.
+    ///
+    /// Plain banners need neither cargo metadata nor git.
+    pub fn resolve_banner(&mut self) -> Result<(), CritError> {
+        let template: String = match self.banner.as_deref() {
+            Some(e) if e == AUTO_BANNER => AUTO_BANNER_TEMPLATE.to_string(),
+            Some(e) if e.contains('{') => e.to_string(),
+            _ => return Ok(()),
+        };
+        let package: CargoPackage = match self.enabled_packages.as_deref() {
+            Some(packages) => packages.first().cloned(),
+            None => self
+                .selected_packages(&CargoMetadata::query()?)?
+                .first()
+                .map(|e| (*e).clone()),
+        }
+        .ok_or(CritError::IOError("no packages selected".to_string()))?;
+        let mut values: collections::BTreeMap<&str, String> = [
+            ("name", package.name.clone()),
+            ("version", package.version.clone()),
+        ]
+        .into_iter()
+        .collect();
+
+        if template.contains("{git_describe}") {
+            values.insert("git_describe", git_describe()?);
+        }
+
+        self.banner = Some(render_template(&template, &values)?);
+        Ok(())
+    }
+
+    /// application_dir resolves the collation directory for an application.
+    pub fn application_dir(
+        &self,
+        target: &str,
+        feature_set: Option<&[String]>,
+        bin_dir_path: &path::Path,
+        application: &Application,
+    ) -> Result<path::PathBuf, CritError> {
+        if let Some(layout) = &self.layout {
+            let rendered: String = render_template(
+                layout,
+                &self.application_values(target, feature_set, application)?,
+            )?;
+            let rendered_path: &path::Path = path::Path::new(&rendered);
+
+            // Keep artifacts within the bin directory.
+            if rendered_path.has_root()
+                || rendered_path
+                    .components()
+                    .any(|e| !matches!(e, path::Component::Normal(_) | path::Component::CurDir))
+            {
+                return Err(CritError::PathRenderError(format!(
+                    "layout {layout:?} renders {rendered:?}, outside of {}",
+                    bin_dir_path.display()
+                )));
+            }
+
+            return Ok(bin_dir_path.join(rendered));
+        }
+
+        let mut dir_pathbuf: path::PathBuf = bin_dir_path.join(target);
+
+        if let Some(feature_set) = feature_set {
+            dir_pathbuf = dir_pathbuf.join(feature_set_label(feature_set));
+        }
+
+        match self.collate_packages {
+            Some(true) => Ok(dir_pathbuf.join(&application.package)),
+            _ => Ok(dir_pathbuf),
+        }
+    }
+
+    /// bin_dir resolves the artifact collation directory.
+    pub fn bin_dir(&self) -> path::PathBuf {
+        if let Some(banner) = &self.banner
+            && !banner.is_empty()
+        {
+            ARTIFACT_ROOT_PATH.join("bin").join(banner)
+        } else {
+            ARTIFACT_ROOT_PATH.join("bin")
+        }
+    }
+
+    /// plan renders the build matrix, without running any builds.
+    pub fn plan(&mut self) -> Result<String, CritError> {
+        self.update_applications()?;
+        self.resolve_banner()?;
+        self.render_plan()
+    }
+
+    /// render_plan formats the build matrix of the cached targets and applications.
+    pub fn render_plan(&self) -> Result<String, CritError> {
+        let builds = self.build_matrix()?;
+        let bin_dir_pathbuf = self.bin_dir();
+        self.check_collisions(&bin_dir_pathbuf)?;
+        let mut lines: Vec<String> = Vec::new();
+
+        for (target, feature_set) in builds {
+            let target_str: &str = &target;
+            let feature_set: Option<&[String]> = feature_set.as_deref();
+            lines.push(format!(
+                "target: {}",
+                Crit::build_label(target_str, feature_set)
+            ));
+            lines.push(format!(
+                "  backend: {}",
+                self.target_backend(target_str)?.name()
+            ));
+            lines.push(format!(
+                "  command: {:?}",
+                self.build_command(target_str, feature_set)?
+            ));
+            lines.push(format!(
+                "  rustflags: {:?}",
+                self.target_rustflags(target_str)?
+            ));
+
+            for dest_pathbuf in
+                self.artifact_destinations(target_str, feature_set, &bin_dir_pathbuf)?
+            {
+                lines.push(format!("  artifact: {}", dest_pathbuf.display()));
+            }
+        }
+
+        for feature_set in self.universal2_feature_sets()? {
+            let feature_set: Option<&[String]> = feature_set.as_deref();
+            lines.push(format!(
+                "target: {}",
+                Crit::build_label(UNIVERSAL2_TARGET, feature_set)
+            ));
+
+            for (dest_pathbuf, _) in self.universal2_sources(feature_set, &bin_dir_pathbuf)? {
+                lines.push(format!("  artifact: {}", dest_pathbuf.display()));
+            }
+        }
+
+        if let Some(true) = self.archive {
+            for target in self.enabled_targets.clone().unwrap_or_default() {
+                lines.push(format!(
+                    "archive: {}",
+                    self.archive_path(&target.to_string(), &bin_dir_pathbuf)?
+                        .display()
+                ));
+            }
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    /// manifest describes the outcome of a run.
+    pub fn manifest(
+        &self,
+        builds: &[Build],
+        reports: &[TargetReport],
+        universal_binaries: &[path::PathBuf],
+        archives: &[(String, path::PathBuf)],
+        checksums: &[checksum::Checksum],
+        bin_dir_path: &path::Path,
+    ) -> Result<manifest::Manifest, CritError> {
+        let digests: collections::BTreeMap<&str, &str> = checksums
+            .iter()
+            .map(|e| (e.path.as_str(), e.sha256.as_str()))
+            .collect();
+        let describe = |pth: &path::Path| -> Result<manifest::ManifestArtifact, CritError> {
+            let relative_path: String = checksum::relative_path(bin_dir_path, pth)?;
+            let sha256: &str = digests
+                .get(relative_path.as_str())
+                .ok_or(CritError::IOError(format!(
+                    "missing checksum: {relative_path}"
+                )))?;
+            manifest::ManifestArtifact::describe(pth, &relative_path, sha256)
+        };
+        let platform_aliases: collections::BTreeMap<String, String> = self.platform_aliases()?;
+        let mut manifest_builds: Vec<manifest::ManifestBuild> = Vec::new();
+
+        for ((target, feature_set), report) in builds.iter().zip(reports) {
+            let feature_set_slice: Option<&[String]> = feature_set.as_deref();
+            let cmd: process::Command = self.build_command(target, feature_set_slice)?;
+            let mut artifacts: Vec<manifest::ManifestArtifact> = Vec::new();
+            let metadata: TargetMetadata = self
+                .metadata(target)
+                .cloned()
+                .unwrap_or(alias::triple_metadata(&Target::parse(target)?));
+
+            if report.status == TargetStatus::Succeeded {
+                for dest_pathbuf in
+                    self.collated_destinations(target, feature_set_slice, bin_dir_path)?
+                {
+                    artifacts.push(describe(&dest_pathbuf)?);
+                }
+            }
+
+            manifest_builds.push(manifest::ManifestBuild {
+                triple: target.clone(),
+                target: Target::parse(target)?,
+                rustc: self.metadata(target).cloned(),
+                os_alias: self.os_alias(&metadata),
+                arch_alias: self.arch_alias(&metadata),
+                alias: platform_aliases.get(target).cloned(),
+                feature_set: feature_set.clone(),
+                status: report.status.to_string(),
+                duration_seconds: report.duration.as_secs_f64(),
+                backend: self.target_backend(target)?.name().to_string(),
+                command: [cmd.get_program()]
+                    .into_iter()
+                    .chain(cmd.get_args())
+                    .map(|e| e.to_string_lossy().to_string())
+                    .collect(),
+                rustflags: self.target_rustflags(target)?,
+                artifacts,
+            });
+        }
+
+        let mut manifest_archives: Vec<manifest::ManifestArchive> = Vec::new();
+
+        for (target, archive_pathbuf) in archives {
+            let format: String = self.target_archive_format(target)?.to_string();
+            let mut artifact: manifest::ManifestArtifact = describe(archive_pathbuf)?;
+            artifact.extension = format.clone();
+            manifest_archives.push(manifest::ManifestArchive {
+                triple: target.clone(),
+                format,
+                artifact,
+            });
+        }
+
+        Ok(manifest::Manifest {
+            version: manifest::MANIFEST_VERSION,
+            crit_version: env!("CARGO_PKG_VERSION").to_string(),
+            banner: self.banner.clone().filter(|e| !e.is_empty()),
+            profile: self.profile_or_default(),
+            builds: manifest_builds,
+            universal_binaries: universal_binaries
+                .iter()
+                .map(|e| describe(e))
+                .collect::<Result<Vec<manifest::ManifestArtifact>, CritError>>()?,
+            archives: manifest_archives,
+        })
+    }
+
+    /// run builds targets.
+    pub fn run(&mut self) -> Result<(), CritError> {
+        if self.enabled_targets.clone().unwrap_or_default().is_empty() {
+            eprintln!("warning: empty targets");
+            return Ok(());
+        }
+
+        self.update_applications()?;
+        self.resolve_banner()?;
+        let builds = self.build_matrix()?;
+        let bin_dir_pathbuf = &self.bin_dir();
+        self.check_collisions(bin_dir_pathbuf)?;
+
+        let jobs = self.jobs.unwrap_or(1);
+
+        let (reports, mut failures) = schedule_builds(
+            &builds,
+            jobs,
+            self.keep_going.unwrap_or(false),
+            |target, feature_set| self.build_target(target, feature_set, bin_dir_pathbuf),
+        )?;
+
+        eprintln!("{}", render_summary(&reports));
+
+        let mut output_pathbufs: Vec<path::PathBuf> = Vec::new();
+
+        for ((target, feature_set), report) in builds.iter().zip(&reports) {
+            if report.status == TargetStatus::Succeeded {
+                output_pathbufs.extend(self.collated_destinations(
+                    target,
+                    feature_set.as_deref(),
+                    bin_dir_pathbuf,
+                )?);
+            }
+        }
+
+        let mut universal_binaries: Vec<path::PathBuf> = Vec::new();
+
+        for feature_set in self.universal2_feature_sets()? {
+            if builds.iter().zip(&reports).any(|((e, e2), report)| {
+                UNIVERSAL2_SOURCE_TARGETS.contains(&e.as_str())
+                    && *e2 == feature_set
+                    && report.status != TargetStatus::Succeeded
+            }) {
+                continue;
+            }
+
+            let label: String = Crit::build_label(UNIVERSAL2_TARGET, feature_set.as_deref());
+
+            match self.build_universal2(feature_set.as_deref(), bin_dir_pathbuf) {
+                Ok(dest_pathbufs) => {
+                    eprintln!("merged {label}");
+                    universal_binaries.extend(dest_pathbufs);
+                }
+                Err(err) => failures.push(CritError::IOError(format!("{label}: {err}"))),
+            }
+        }
+
+        output_pathbufs.extend(universal_binaries.clone());
+        let mut archives: Vec<(String, path::PathBuf)> = Vec::new();
+
+        if let Some(true) = self.archive {
+            for target in self.enabled_targets.clone().unwrap_or_default() {
+                let target_str: &str = &target.to_string();
+
+                if builds.iter().zip(&reports).any(|((e, _), report)| {
+                    e == target_str && report.status != TargetStatus::Succeeded
+                }) {
+                    continue;
+                }
+
+                match self.archive_target(target_str, bin_dir_pathbuf) {
+                    Ok(archive_pathbuf) => {
+                        eprintln!("archived {target_str} to {:?}", archive_pathbuf);
+                        output_pathbufs.push(archive_pathbuf.clone());
+                        archives.push((target_str.to_string(), archive_pathbuf));
+                    }
+                    Err(err) => failures.push(CritError::IOError(format!("{target_str}: {err}"))),
+                }
+            }
+        }
+
+        fs::create_dir_all(bin_dir_pathbuf).map_err(|err| CritError::IOError(err.to_string()))?;
+        let checksums: Vec<checksum::Checksum> =
+            checksum::write_checksums(bin_dir_pathbuf, &output_pathbufs)?;
+        let manifest: manifest::Manifest = self.manifest(
+            &builds,
+            &reports,
+            &universal_binaries,
+            &archives,
+            &checksums,
+            bin_dir_pathbuf,
+        )?;
+        manifest::write_manifest(bin_dir_pathbuf, &manifest)?;
+
+        if !failures.is_empty() {
+            return Err(CritError::TargetErrors(failures));
+        }
+
+        eprintln!("artifacts copied to {:?}", bin_dir_pathbuf);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_profile_dir() {
+    let profile_dir = |profile: Option<&str>| {
+        Crit {
+            profile: profile.map(|e| e.to_string()),
+            ..Default::default()
+        }
+        .profile_dir()
+    };
+    assert_eq!(profile_dir(None), "release");
+    assert_eq!(profile_dir(Some("dev")), "debug");
+    assert_eq!(profile_dir(Some("release")), "release");
+    assert_eq!(profile_dir(Some("dist")), "dist");
+}
+
+#[test]
+fn test_feature_set_applications() -> Result<(), CritError> {
+    let application = |name: &str, required_features: &[&str]| Application {
+        package: "hello".to_string(),
+        name: name.to_string(),
+        required_features: required_features.iter().map(|e| e.to_string()).collect(),
+        crate_types: vec!["bin".to_string()],
+    };
+    let crit = Crit {
+        enabled_applications: Some(vec![
+            application("hello", &[]),
+            application("hello-tls", &["tls"]),
+            application("hello-cli", &["cli"]),
+        ]),
+        enabled_packages: Some(vec![CargoPackage {
+            name: "hello".to_string(),
+            features: [
+                ("default".to_string(), vec!["cli".to_string()]),
+                ("cli".to_string(), Vec::new()),
+                ("tls".to_string(), Vec::new()),
+            ]
+            .into(),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    };
+    let names = |feature_set: Option<&[String]>| -> Vec<String> {
+        crit.feature_set_applications(feature_set)
+            .into_iter()
+            .map(|e| e.name)
+            .collect()
+    };
+
+    assert_eq!(names(None), vec!["hello", "hello-tls", "hello-cli"]);
+    assert_eq!(
+        crit.feature_args(None)?,
+        vec!["--features", "hello/cli,hello/tls"]
+    );
+
+    let tls: Vec<String> = vec!["tls".to_string()];
+    assert_eq!(names(Some(&tls)), vec!["hello", "hello-tls"]);
+    assert_eq!(
+        crit.feature_args(Some(&tls))?,
+        vec!["--no-default-features", "--features", "hello/tls"]
+    );
+
+    let default: Vec<String> = vec!["default".to_string()];
+    assert_eq!(names(Some(&default)), vec!["hello", "hello-cli"]);
+    assert_eq!(
+        crit.feature_args(Some(&default))?,
+        vec!["--features", "hello/cli"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_check_collisions() -> Result<(), CritError> {
+    let application = |package: &str, name: &str| Application {
+        package: package.to_string(),
+        name: name.to_string(),
+        crate_types: vec!["bin".to_string()],
+        ..Default::default()
+    };
+    let targets: Vec<Target> = vec![
+        Target::parse("x86_64-unknown-linux-gnu")?,
+        Target::parse("x86_64-unknown-linux-musl")?,
+    ];
+    let crit = |name: &str| Crit {
+        layout: Some("{os_alias}-{arch_alias}".to_string()),
+        name: Some(name.to_string()),
+        enabled_targets: Some(targets.clone()),
+        target_metadata: Some(
+            targets
+                .iter()
+                .map(|e| {
+                    (
+                        e.to_string(),
+                        TargetMetadata {
+                            arch: "x86_64".to_string(),
+                            os: "linux".to_string(),
+                            env: e.abi.clone().unwrap_or_default(),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+        ),
+        enabled_applications: Some(vec![application("a", "a"), application("b", "b")]),
+        ..Default::default()
+    };
+    let bin_dir_path: &path::Path = path::Path::new("bin");
+
+    assert_eq!(
+        crit("{bin}-{abi}").artifact_destinations(
+            "x86_64-unknown-linux-musl",
+            None,
+            bin_dir_path
+        )?,
+        vec![
+            path::PathBuf::from("bin/linux-amd64/a-musl"),
+            path::PathBuf::from("bin/linux-amd64/b-musl"),
+        ]
+    );
+    assert!(crit("{bin}-{abi}").check_collisions(bin_dir_path).is_ok());
+    assert!(
+        crit("{bin}-{target_env}")
+            .check_collisions(bin_dir_path)
+            .is_ok()
+    );
+    assert!(crit("{bin}").check_collisions(bin_dir_path).is_err());
+    assert!(crit("{abi}").check_collisions(bin_dir_path).is_err());
+
+    for layout in ["../{os_alias}", "/{os_alias}", "{os_alias}/../.."] {
+        let escaping_crit = Crit {
+            layout: Some(layout.to_string()),
+            ..crit("{bin}-{abi}")
+        };
+        assert!(escaping_crit.check_collisions(bin_dir_path).is_err());
+    }
+
+    let emscripten_target: Target = Target::parse("wasm32-unknown-emscripten")?;
+    let emscripten_crit = Crit {
+        layout: Some(String::new()),
+        name: Some("{bin}-{os}".to_string()),
+        enabled_targets: Some(vec![emscripten_target.clone()]),
+        target_metadata: Some(
+            [(
+                emscripten_target.to_string(),
+                TargetMetadata {
+                    arch: "wasm32".to_string(),
+                    os: "emscripten".to_string(),
+                    family: vec!["wasm".to_string(), "unix".to_string()],
+                    ..Default::default()
+                },
+            )]
+            .into(),
+        ),
+        enabled_applications: Some(vec![application("a", "a")]),
+        ..Default::default()
+    };
+    assert_eq!(
+        emscripten_crit.artifact_destinations("wasm32-unknown-emscripten", None, bin_dir_path)?,
+        vec![
+            path::PathBuf::from("bin/a-emscripten.js"),
+            path::PathBuf::from("bin/a.wasm"),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_universal2_sources() -> Result<(), CritError> {
+    let macos = |arch: &str| TargetMetadata {
+        arch: arch.to_string(),
+        vendor: "apple".to_string(),
+        os: "macos".to_string(),
+        ..Default::default()
+    };
+    let crit = |x86_64_metadata: TargetMetadata| Crit {
+        layout: Some(String::new()),
+        name: Some("{bin}-{arch}".to_string()),
+        target_metadata: Some(
+            [
+                (UNIVERSAL2_SOURCE_TARGETS[0].to_string(), x86_64_metadata),
+                (UNIVERSAL2_SOURCE_TARGETS[1].to_string(), macos("aarch64")),
+                (UNIVERSAL2_TARGET.to_string(), macos("universal2")),
+            ]
+            .into(),
+        ),
+        enabled_applications: Some(vec![
+            Application {
+                package: "hello".to_string(),
+                name: "hello".to_string(),
+                crate_types: vec!["bin".to_string()],
+                ..Default::default()
+            },
+            Application {
+                package: "hello".to_string(),
+                name: "hello-cli".to_string(),
+                crate_types: vec!["bin".to_string()],
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    };
+    let bin_dir_path: &path::Path = path::Path::new("bin");
+
+    assert_eq!(
+        crit(macos("x86_64")).universal2_sources(None, bin_dir_path)?,
+        vec![
+            (
+                path::PathBuf::from("bin/hello-universal2"),
+                vec![
+                    (
+                        "x86_64".to_string(),
+                        path::PathBuf::from("bin/hello-x86_64")
+                    ),
+                    (
+                        "aarch64".to_string(),
+                        path::PathBuf::from("bin/hello-aarch64")
+                    ),
+                ]
+            ),
+            (
+                path::PathBuf::from("bin/hello-cli-universal2"),
+                vec![
+                    (
+                        "x86_64".to_string(),
+                        path::PathBuf::from("bin/hello-cli-x86_64")
+                    ),
+                    (
+                        "aarch64".to_string(),
+                        path::PathBuf::from("bin/hello-cli-aarch64")
+                    ),
+                ]
+            ),
+        ]
+    );
+
+    let mismatched_crit = crit(TargetMetadata {
+        os: "windows".to_string(),
+        ..macos("x86_64")
+    });
+    assert!(
+        mismatched_crit
+            .universal2_sources(None, bin_dir_path)
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_render_plan() -> Result<(), CritError> {
+    let target: Target = Target::parse("x86_64-unknown-linux-musl")?;
+    let crit = Crit {
+        banner: Some("hello-0.0.1".to_string()),
+        backend: Some([("*".to_string(), "cargo".to_string())].into()),
+        rustflags: Some(
+            [(
+                "*-musl*".to_string(),
+                "-C target-feature=+crt-static".to_string(),
+            )]
+            .into(),
+        ),
+        archive: Some(true),
+        enabled_targets: Some(vec![target.clone()]),
+        target_metadata: Some(
+            [(
+                target.to_string(),
+                TargetMetadata {
+                    arch: "x86_64".to_string(),
+                    os: "linux".to_string(),
+                    env: "musl".to_string(),
+                    ..Default::default()
+                },
+            )]
+            .into(),
+        ),
+        enabled_applications: Some(vec![Application {
+            package: "hello".to_string(),
+            name: "hello".to_string(),
+            crate_types: vec!["bin".to_string()],
+            ..Default::default()
+        }]),
+        enabled_packages: Some(vec![CargoPackage {
+            name: "hello".to_string(),
+            version: "0.0.1".to_string(),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    };
+    let plan: String = crit.render_plan()?;
+    let lines: Vec<&str> = plan.lines().collect();
+
+    assert_eq!(lines[0], "target: x86_64-unknown-linux-musl");
+    assert_eq!(lines[1], "  backend: cargo");
+    assert!(lines[2].starts_with("  command: "));
+    assert!(lines[2].contains(r#""cargo" "build" "--target-dir" ".crit/cross/x86_64-unknown-linux-musl" "--target" "x86_64-unknown-linux-musl" "--profile" "release""#));
+    assert!(lines[3].starts_with("  rustflags: "));
+    assert!(lines[3].contains("-C target-feature=+crt-static"));
+    assert_eq!(
+        lines[4..],
+        [
+            "  artifact: .crit/bin/hello-0.0.1/x86_64-unknown-linux-musl/hello",
+            "archive: .crit/bin/hello-0.0.1/hello-0.0.1-x86_64-unknown-linux-musl.tar.gz",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_alias_winners() -> Result<(), CritError> {
+    let candidates: Vec<(String, TargetMetadata)> = [
+        "x86_64-pc-windows-gnu",
+        "x86_64-pc-windows-msvc",
+        "x86_64-unknown-linux-gnu",
+        "x86_64-unknown-linux-musl",
+        "aarch64-apple-darwin",
+        "i586-pc-windows-msvc",
+        "i686-pc-windows-gnu",
+        "i686-pc-windows-msvc",
+        "i586-unknown-linux-gnu",
+        "i586-unknown-linux-musl",
+        "i686-unknown-linux-gnu",
+        "i686-unknown-linux-musl",
+        "arm-unknown-linux-gnueabi",
+        "arm-unknown-linux-gnueabihf",
+        "armv5te-unknown-linux-gnueabi",
+        "armv7-unknown-linux-gnueabihf",
+        "armv7-unknown-linux-musleabihf",
+    ]
+    .iter()
+    .map(|e| Target::parse(e).map(|target| (e.to_string(), alias::triple_metadata(&target))))
+    .collect::<Result<Vec<(String, TargetMetadata)>, CritError>>()?;
+
+    let winners = Crit::default().alias_winners(&candidates)?;
+    assert_eq!(winners["windows/amd64"], "x86_64-pc-windows-msvc");
+    assert_eq!(winners["linux/amd64"], "x86_64-unknown-linux-gnu");
+    assert_eq!(winners["darwin/arm64"], "aarch64-apple-darwin");
+    assert_eq!(winners["windows/386"], "i686-pc-windows-msvc");
+    assert_eq!(winners["linux/386"], "i686-unknown-linux-gnu");
+    assert_eq!(winners["linux/arm"], "armv7-unknown-linux-gnueabihf");
+
+    let crit = Crit {
+        alias_precedence: Some(vec!["*-musl".to_string()]),
+        os_aliases: Some([("macos".to_string(), "macos".to_string())].into()),
+        ..Default::default()
+    };
+    let winners = crit.alias_winners(&candidates)?;
+    assert_eq!(winners["linux/amd64"], "x86_64-unknown-linux-musl");
+    assert_eq!(winners["windows/amd64"], "x86_64-pc-windows-gnu");
+    assert_eq!(winners["macos/arm64"], "aarch64-apple-darwin");
+    assert_eq!(winners["linux/386"], "i586-unknown-linux-musl");
+    Ok(())
+}
+
+#[test]
+fn test_select_targets() -> Result<(), CritError> {
+    let metadata = |arch: &str, vendor: &str, os: &str, env: &str, abi: &str| TargetMetadata {
+        arch: arch.to_string(),
+        vendor: vendor.to_string(),
+        os: os.to_string(),
+        env: env.to_string(),
+        abi: abi.to_string(),
+        ..Default::default()
+    };
+    let target_metadata: collections::BTreeMap<String, TargetMetadata> = [
+        (
+            "aarch64-unknown-linux-gnu",
+            metadata("aarch64", "unknown", "linux", "gnu", ""),
+        ),
+        (
+            "armv7-unknown-linux-gnueabihf",
+            metadata("arm", "unknown", "linux", "gnu", "eabihf"),
+        ),
+        (
+            "x86_64-pc-windows-msvc",
+            metadata("x86_64", "pc", "windows", "msvc", ""),
+        ),
+        (
+            "x86_64-unknown-linux-gnu",
+            metadata("x86_64", "unknown", "linux", "gnu", ""),
+        ),
+    ]
+    .into_iter()
+    .map(|(triple, e)| (triple.to_string(), e))
+    .collect();
+    let available_targets: Vec<Target> = target_metadata
+        .keys()
+        .map(String::as_str)
+        .chain(["x86_64-unknown-linux-none"])
+        .map(Target::parse)
+        .collect::<Result<Vec<Target>, CritError>>()?;
+    let crit = Crit {
+        arch: vec!["arm".to_string(), "x86_64".to_string()],
+        vendor: vec!["unknown".to_string()],
+        os: vec!["linux".to_string()],
+        abi: vec![String::new(), "eabihf".to_string()],
+        target_excludes: Some(vec!["x86_64-unknown-linux-gnu".to_string()]),
+        ..Default::default()
+    };
+    let selections: Vec<TargetSelection> =
+        crit.select_targets(available_targets.clone(), &target_metadata)?;
+    let reasons: Vec<(&str, bool, &str)> = selections
+        .iter()
+        .map(|e| (e.triple.as_str(), e.included, e.reason.as_str()))
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            (
+                "aarch64-unknown-linux-gnu",
+                false,
+                "excluded: arch 'aarch64' not enabled"
+            ),
+            ("armv7-unknown-linux-gnueabihf", true, "included"),
+            (
+                "x86_64-pc-windows-msvc",
+                false,
+                "excluded: vendor 'pc' not enabled"
+            ),
+            (
+                "x86_64-unknown-linux-gnu",
+                false,
+                "excluded by target_excludes"
+            ),
+            (
+                "x86_64-unknown-linux-none",
+                false,
+                "excluded: unknown to rustc"
+            ),
+        ]
+    );
+
+    let invalid_crit = Crit {
+        arch: vec!["armv7".to_string()],
+        ..Default::default()
+    };
+    assert!(
+        invalid_crit
+            .select_targets(available_targets, &target_metadata)
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_selected_packages() -> Result<(), CritError> {
+    let cwd: path::PathBuf = canonical_current_dir()?;
+    let package = |name: &str, dir: &path::Path| CargoPackage {
+        name: name.to_string(),
+        version: "0.0.1".to_string(),
+        id: format!("{name} 0.0.1"),
+        manifest_path: dir.join("Cargo.toml").display().to_string(),
+        ..Default::default()
+    };
+    let metadata = CargoMetadata {
+        packages: vec![
+            package("root", &cwd),
+            package("a", &cwd.join("a")),
+            package("b", &cwd.join("b")),
+        ],
+        workspace_members: vec![
+            "root 0.0.1".to_string(),
+            "a 0.0.1".to_string(),
+            "b 0.0.1".to_string(),
+        ],
+        workspace_default_members: vec!["root 0.0.1".to_string(), "a 0.0.1".to_string()],
+        workspace_root: cwd.display().to_string(),
+    };
+    let names = |crit: &Crit, metadata: &CargoMetadata| -> Result<Vec<String>, CritError> {
+        Ok(crit
+            .selected_packages(metadata)?
+            .into_iter()
+            .map(|e| e.name.clone())
+            .collect())
+    };
+
+    let package_crit = Crit {
+        packages: Some(vec!["b".to_string()]),
+        ..Default::default()
+    };
+    assert_eq!(names(&package_crit, &metadata)?, vec!["b"]);
+
+    let unknown_crit = Crit {
+        packages: Some(vec!["c".to_string()]),
+        ..Default::default()
+    };
+    assert!(names(&unknown_crit, &metadata).is_err());
+
+    let workspace_crit = Crit {
+        workspace: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(names(&workspace_crit, &metadata)?, vec!["root", "a", "b"]);
+
+    assert_eq!(names(&Crit::default(), &metadata)?, vec!["root"]);
+
+    // Virtual workspaces lack a current package, and so build their default members.
+    let virtual_metadata = CargoMetadata {
+        packages: metadata.packages[1..].to_vec(),
+        workspace_members: metadata.workspace_members[1..].to_vec(),
+        workspace_default_members: vec!["a 0.0.1".to_string()],
+        ..metadata.clone()
+    };
+    assert_eq!(names(&Crit::default(), &virtual_metadata)?, vec!["a"]);
+    Ok(())
+}
+
+#[test]
+fn test_resolve_banner() -> Result<(), CritError> {
+    let banner = |banner: &str| -> Result<Option<String>, CritError> {
+        let mut crit = Crit {
+            banner: Some(banner.to_string()),
+            enabled_packages: Some(vec![CargoPackage {
+                name: "hello".to_string(),
+                version: "0.0.1".to_string(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        crit.resolve_banner()?;
+        Ok(crit.banner)
+    };
+    assert_eq!(banner("auto")?, Some("hello-0.0.1".to_string()));
+    assert_eq!(
+        banner("{name}_v{version}")?,
+        Some("hello_v0.0.1".to_string())
+    );
+    assert_eq!(banner("hello")?, Some("hello".to_string()));
+    assert!(banner("{nmae}").is_err());
+    Ok(())
+}
+
 /// clean_containers removes leftover cross Docker containers.
 pub fn clean_containers(debug: bool) -> Result<(), CritError> {
     let cross_toml_path: &path::Path = path::Path::new("Cross.toml");
@@ -0,0 +1,220 @@
+//! macho merges per-architecture Mach-O files into universal (fat) files, in place of Apple's lipo.
+
+use crate::CritError;
+
+use std::fs;
+use std::path;
+
+/// FAT_MAGIC denotes the big endian magic number of universal files.
+pub static FAT_MAGIC: u32 = 0xcafe_babe;
+
+/// MH_MAGIC denotes the native magic number of 32-bit Mach-O files.
+pub static MH_MAGIC: u32 = 0xfeed_face;
+
+/// MH_MAGIC_64 denotes the native magic number of 64-bit Mach-O files.
+pub static MH_MAGIC_64: u32 = 0xfeed_facf;
+
+/// AR_MAGIC denotes the magic string of static libraries.
+pub static AR_MAGIC: &[u8] = b"!<arch>\n";
+
+/// FAT_ALIGN denotes the power of two alignment of each architecture slice (16 KiB pages).
+pub static FAT_ALIGN: u32 = 14;
+
+/// CPU_TYPE_X86_64 denotes the Mach-O cputype of x86_64.
+pub static CPU_TYPE_X86_64: u32 = 0x0100_0007;
+
+/// CPU_TYPE_ARM64 denotes the Mach-O cputype of aarch64.
+pub static CPU_TYPE_ARM64: u32 = 0x0100_000c;
+
+/// cpu_type resolves the Mach-O (cputype, cpusubtype) of a rustc arch.
+pub fn cpu_type(arch: &str) -> Option<(u32, u32)> {
+    match arch {
+        "x86_64" => Some((CPU_TYPE_X86_64, 3)),
+        "aarch64" => Some((CPU_TYPE_ARM64, 0)),
+        _ => None,
+    }
+}
+
+/// thin_cpu_type reads the (cputype, cpusubtype) of a single architecture Mach-O file.
+pub fn thin_cpu_type(data: &[u8]) -> Option<(u32, u32)> {
+    let field =
+        |offset: usize| -> Option<[u8; 4]> { data.get(offset..offset + 4)?.try_into().ok() };
+    let magic: [u8; 4] = field(0)?;
+
+    if u32::from_le_bytes(magic) == MH_MAGIC_64 || u32::from_le_bytes(magic) == MH_MAGIC {
+        Some((u32::from_le_bytes(field(4)?), u32::from_le_bytes(field(8)?)))
+    } else if u32::from_be_bytes(magic) == MH_MAGIC_64 || u32::from_be_bytes(magic) == MH_MAGIC {
+        Some((u32::from_be_bytes(field(4)?), u32::from_be_bytes(field(8)?)))
+    } else {
+        None
+    }
+}
+
+/// Slice models the contents of one architecture within a universal file.
+pub struct Slice {
+    /// cputype denotes the Mach-O CPU type.
+    pub cputype: u32,
+
+    /// cpusubtype denotes the Mach-O CPU subtype.
+    pub cpusubtype: u32,
+
+    /// data denotes the single architecture file contents.
+    pub data: Vec<u8>,
+}
+
+/// render_fat lays out a universal file, with each slice aligned to FAT_ALIGN.
+pub fn render_fat(slices: &[Slice]) -> Result<Vec<u8>, CritError> {
+    let align: usize = 1 << FAT_ALIGN;
+    let mut header: Vec<u8> = Vec::new();
+    let mut body: Vec<u8> = Vec::new();
+    header.extend(FAT_MAGIC.to_be_bytes());
+    header.extend((slices.len() as u32).to_be_bytes());
+
+    let header_len: usize = 8 + 20 * slices.len();
+    let mut offset: usize = header_len.next_multiple_of(align);
+
+    for (i, slice) in slices.iter().enumerate() {
+        if slices[..i].iter().any(|e| e.cputype == slice.cputype) {
+            return Err(CritError::IOError(format!(
+                "duplicate Mach-O cputype: {:#x}",
+                slice.cputype
+            )));
+        }
+
+        let offset_u32: u32 = u32::try_from(offset)
+            .map_err(|_| CritError::IOError("universal file exceeds 4 GiB".to_string()))?;
+        let size_u32: u32 = u32::try_from(slice.data.len())
+            .map_err(|_| CritError::IOError("universal file exceeds 4 GiB".to_string()))?;
+
+        for field in [
+            slice.cputype,
+            slice.cpusubtype,
+            offset_u32,
+            size_u32,
+            FAT_ALIGN,
+        ] {
+            header.extend(field.to_be_bytes());
+        }
+
+        body.resize(offset - header_len, 0);
+        body.extend(&slice.data);
+        offset = (offset + slice.data.len()).next_multiple_of(align);
+    }
+
+    header.extend(body);
+    Ok(header)
+}
+
+#[test]
+fn test_render_fat() -> Result<(), CritError> {
+    let thin = |cputype: u32, cpusubtype: u32| {
+        let mut data: Vec<u8> = Vec::new();
+        data.extend(MH_MAGIC_64.to_le_bytes());
+        data.extend(cputype.to_le_bytes());
+        data.extend(cpusubtype.to_le_bytes());
+        data.extend([0u8; 20]);
+        data
+    };
+    let x86_64 = thin(CPU_TYPE_X86_64, 3);
+    let arm64 = thin(CPU_TYPE_ARM64, 0);
+    assert_eq!(thin_cpu_type(&x86_64), cpu_type("x86_64"));
+    assert_eq!(thin_cpu_type(&arm64), cpu_type("aarch64"));
+    assert_eq!(thin_cpu_type(AR_MAGIC), None);
+
+    let fat: Vec<u8> = render_fat(&[
+        Slice {
+            cputype: CPU_TYPE_X86_64,
+            cpusubtype: 3,
+            data: x86_64.clone(),
+        },
+        Slice {
+            cputype: CPU_TYPE_ARM64,
+            cpusubtype: 0,
+            data: arm64.clone(),
+        },
+    ])?;
+    let word = |offset: usize| u32::from_be_bytes(fat[offset..offset + 4].try_into().unwrap());
+    assert_eq!(word(0), FAT_MAGIC);
+    assert_eq!(word(4), 2);
+    assert_eq!(
+        [word(8), word(12), word(16), word(20), word(24)],
+        [CPU_TYPE_X86_64, 3, 0x4000, x86_64.len() as u32, FAT_ALIGN]
+    );
+    assert_eq!(
+        [word(28), word(32), word(36), word(40), word(44)],
+        [CPU_TYPE_ARM64, 0, 0x8000, arm64.len() as u32, FAT_ALIGN]
+    );
+    assert_eq!(&fat[0x4000..0x4000 + x86_64.len()], x86_64.as_slice());
+    assert_eq!(&fat[0x8000..], arm64.as_slice());
+
+    assert!(
+        render_fat(&[
+            Slice {
+                cputype: CPU_TYPE_ARM64,
+                cpusubtype: 0,
+                data: arm64.clone(),
+            },
+            Slice {
+                cputype: CPU_TYPE_ARM64,
+                cpusubtype: 0,
+                data: arm64,
+            },
+        ])
+        .is_err()
+    );
+    Ok(())
+}
+
+/// write_universal merges single architecture Mach-O files or static libraries,
+/// given as (rustc arch, path) pairs, into a universal file.
+pub fn write_universal(
+    dest_path: &path::Path,
+    sources: &[(String, path::PathBuf)],
+) -> Result<(), CritError> {
+    let mut slices: Vec<Slice> = Vec::new();
+
+    for (arch, source_pathbuf) in sources {
+        let source_str: String = source_pathbuf.display().to_string();
+        let (cputype, default_cpusubtype) = cpu_type(arch).ok_or(CritError::IOError(format!(
+            "unsupported universal binary arch: {arch}"
+        )))?;
+        let data: Vec<u8> = fs::read(source_pathbuf)
+            .map_err(|err| CritError::IOError(format!("unable to read {source_str}: {err}")))?;
+
+        let cpusubtype: u32 = match thin_cpu_type(&data) {
+            Some((actual_cputype, _)) if actual_cputype != cputype => {
+                return Err(CritError::IOError(format!(
+                    "{source_str}: Mach-O cputype {actual_cputype:#x} does not match {arch}"
+                )));
+            }
+            Some((_, cpusubtype)) => cpusubtype,
+            None if data.starts_with(AR_MAGIC) => default_cpusubtype,
+            None => {
+                return Err(CritError::IOError(format!(
+                    "{source_str}: not a single architecture Mach-O file or static library"
+                )));
+            }
+        };
+
+        slices.push(Slice {
+            cputype,
+            cpusubtype,
+            data,
+        });
+    }
+
+    let dest_str: String = dest_path.display().to_string();
+    fs::write(dest_path, render_fat(&slices)?)
+        .map_err(|err| CritError::IOError(format!("unable to write {dest_str}: {err}")))?;
+
+    // Preserve the executable bit of the inputs.
+    if let Some((_, source_pathbuf)) = sources.first() {
+        let permissions: fs::Permissions = fs::metadata(source_pathbuf)
+            .map_err(|err| CritError::IOError(err.to_string()))?
+            .permissions();
+        fs::set_permissions(dest_path, permissions)
+            .map_err(|err| CritError::IOError(err.to_string()))?;
+    }
+
+    Ok(())
+}
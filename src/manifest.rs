@@ -0,0 +1,268 @@
+//! manifest describes build outputs for downstream tooling.
+
+use crate::{CritError, Target, TargetMetadata, object};
+
+use serde::{Deserialize, Serialize};
+
+use std::fs;
+use std::path;
+
+/// MANIFEST_FILENAME denotes the build manifest file name.
+pub static MANIFEST_FILENAME: &str = "manifest.json";
+
+/// MANIFEST_VERSION denotes the build manifest schema version.
+///
+/// Additive changes keep the version. Removing or changing the meaning of fields increments the version.
+pub static MANIFEST_VERSION: u32 = 1;
+
+/// Manifest models a build manifest.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Manifest {
+    /// version denotes the manifest schema version.
+    pub version: u32,
+
+    /// crit_version denotes the crit release that generated the manifest.
+    pub crit_version: String,
+
+    /// banner denotes the artifact directory label, if any.
+    pub banner: Option<String>,
+
+    /// profile denotes the cargo build profile.
+    pub profile: String,
+
+    /// builds collects target builds.
+    pub builds: Vec<ManifestBuild>,
+
+    /// universal_binaries collects macOS universal binaries (see universal2).
+    pub universal_binaries: Vec<ManifestArtifact>,
+
+    /// archives collects target archives.
+    pub archives: Vec<ManifestArchive>,
+}
+
+/// ManifestBuild models a target build.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ManifestBuild {
+    /// triple denotes the Rust target triple.
+    pub triple: String,
+
+    /// target denotes the positional triple fields.
+    pub target: Target,
+
+    /// rustc denotes the target configuration reported by rustc, if known.
+    pub rustc: Option<TargetMetadata>,
+
+    /// os_alias denotes the GOOS style operating system name, e.g. "linux".
+    pub os_alias: String,
+
+    /// arch_alias denotes the GOARCH style chipset name, e.g. "amd64".
+    pub arch_alias: String,
+
+    /// alias denotes the GOOS/GOARCH style platform name, e.g. "linux/amd64",
+    /// unless another target with the same alias takes precedence.
+    pub alias: Option<String>,
+
+    /// feature_set denotes the cargo features of the build, if building a feature matrix.
+    pub feature_set: Option<Vec<String>>,
+
+    /// status denotes the build outcome: "succeeded", "failed", or "skipped".
+    pub status: String,
+
+    /// duration_seconds denotes the elapsed build time.
+    pub duration_seconds: f64,
+
+    /// backend denotes the build backend.
+    pub backend: String,
+
+    /// command denotes the build program and arguments.
+    pub command: Vec<String>,
+
+    /// rustflags denotes the RUSTFLAGS of the build.
+    pub rustflags: String,
+
+    /// artifacts collects the collated files of successful builds.
+    pub artifacts: Vec<ManifestArtifact>,
+}
+
+/// ManifestArtifact models a collated file.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ManifestArtifact {
+    /// path denotes the slash separated file location, relative to the manifest directory.
+    pub path: String,
+
+    /// size denotes the file size in bytes.
+    pub size: u64,
+
+    /// extension denotes the file extension, or an empty string for extensionless files.
+    pub extension: String,
+
+    /// sha256 denotes the lowercase hexadecimal SHA-256 digest.
+    pub sha256: String,
+
+    /// object_format denotes the detected executable format, e.g. "elf", if any.
+    pub object_format: Option<String>,
+}
+
+/// ManifestArchive models a target archive.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ManifestArchive {
+    /// triple denotes the Rust target triple.
+    pub triple: String,
+
+    /// format denotes the archive format: "tar.gz", "tar.xz", or "zip".
+    pub format: String,
+
+    /// artifact describes the archive file.
+    #[serde(flatten)]
+    pub artifact: ManifestArtifact,
+}
+
+#[test]
+fn test_manifest_schema() -> Result<(), CritError> {
+    let keys = |value: &serde_json::Value| -> Vec<String> {
+        let mut keys: Vec<String> = value
+            .as_object()
+            .map(|e| e.keys().cloned().collect())
+            .unwrap_or_default();
+        keys.sort();
+        keys
+    };
+    let sorted = |fields: &[&str]| -> Vec<String> {
+        let mut fields: Vec<String> = fields.iter().map(|e| e.to_string()).collect();
+        fields.sort();
+        fields
+    };
+    let artifact_fields: [&str; 5] = ["path", "size", "extension", "sha256", "object_format"];
+    let manifest = Manifest {
+        version: MANIFEST_VERSION,
+        builds: vec![ManifestBuild {
+            rustc: Some(TargetMetadata::default()),
+            artifacts: vec![ManifestArtifact::default()],
+            ..Default::default()
+        }],
+        universal_binaries: vec![ManifestArtifact::default()],
+        archives: vec![ManifestArchive::default()],
+        ..Default::default()
+    };
+    let value: serde_json::Value =
+        serde_json::to_value(&manifest).map_err(|err| CritError::IOError(err.to_string()))?;
+
+    assert_eq!(MANIFEST_VERSION, 1);
+    assert_eq!(value["version"], 1);
+    assert_eq!(
+        keys(&value),
+        sorted(&[
+            "version",
+            "crit_version",
+            "banner",
+            "profile",
+            "builds",
+            "universal_binaries",
+            "archives",
+        ])
+    );
+    assert_eq!(
+        keys(&value["builds"][0]),
+        sorted(&[
+            "triple",
+            "target",
+            "rustc",
+            "os_alias",
+            "arch_alias",
+            "alias",
+            "feature_set",
+            "status",
+            "duration_seconds",
+            "backend",
+            "command",
+            "rustflags",
+            "artifacts",
+        ])
+    );
+    assert_eq!(
+        keys(&value["builds"][0]["target"]),
+        sorted(&["arch", "vendor", "os", "abi"])
+    );
+    assert_eq!(
+        keys(&value["builds"][0]["rustc"]),
+        sorted(&[
+            "arch",
+            "vendor",
+            "os",
+            "env",
+            "abi",
+            "family",
+            "pointer_width",
+            "endian",
+        ])
+    );
+    assert_eq!(
+        keys(&value["builds"][0]["artifacts"][0]),
+        sorted(&artifact_fields)
+    );
+    assert_eq!(
+        keys(&value["universal_binaries"][0]),
+        sorted(&artifact_fields)
+    );
+    assert_eq!(
+        keys(&value["archives"][0]),
+        sorted(&[&artifact_fields[..], &["triple", "format"]].concat())
+    );
+
+    // The documented example must survive a round trip unchanged.
+    let manifest_doc: &str = include_str!("../MANIFEST.md");
+    let example_json: &str = manifest_doc
+        .split("```json\n")
+        .nth(1)
+        .and_then(|e| e.split("```").next())
+        .ok_or(CritError::IOError(
+            "missing MANIFEST.md example".to_string(),
+        ))?;
+    let example_value: serde_json::Value =
+        serde_json::from_str(example_json).map_err(|err| CritError::IOError(err.to_string()))?;
+    let example_manifest: Manifest = serde_json::from_value(example_value.clone())
+        .map_err(|err| CritError::IOError(err.to_string()))?;
+    assert_eq!(example_manifest.version, MANIFEST_VERSION);
+    assert_eq!(
+        serde_json::to_value(&example_manifest)
+            .map_err(|err| CritError::IOError(err.to_string()))?,
+        example_value
+    );
+    Ok(())
+}
+
+impl ManifestArtifact {
+    /// describe inspects a file, given its manifest relative path and digest.
+    pub fn describe(
+        pth: &path::Path,
+        relative_path: &str,
+        sha256: &str,
+    ) -> Result<ManifestArtifact, CritError> {
+        let size: u64 = fs::metadata(pth).map(|e| e.len()).map_err(|err| {
+            CritError::IOError(format!("unable to stat {}: {err}", pth.display()))
+        })?;
+
+        Ok(ManifestArtifact {
+            path: relative_path.to_string(),
+            size,
+            extension: pth
+                .extension()
+                .map(|e| e.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            sha256: sha256.to_string(),
+            object_format: object::read_header(pth)?.map(|e| e.format.to_string()),
+        })
+    }
+}
+
+/// write_manifest records a build manifest within a directory.
+pub fn write_manifest(dir_path: &path::Path, manifest: &Manifest) -> Result<(), CritError> {
+    let manifest_json: String = serde_json::to_string_pretty(manifest)
+        .map_err(|err| CritError::IOError(err.to_string()))?;
+
+    fs::write(
+        dir_path.join(MANIFEST_FILENAME),
+        format!("{manifest_json}\n"),
+    )
+    .map_err(|err| CritError::IOError(format!("unable to write manifest: {err}")))
+}
@@ -0,0 +1,441 @@
+//! object reads the headers of ELF, PE, Mach-O, and wasm files, to check artifacts against their target.
+
+use crate::{CritError, TargetMetadata, macho};
+
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path;
+
+/// HEADER_LIMIT denotes the number of leading bytes read from each artifact.
+pub static HEADER_LIMIT: u64 = 64 * 1024;
+
+/// ObjectFormat models executable and library file formats.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ObjectFormat {
+    /// Elf denotes Executable and Linkable Format files.
+    Elf,
+
+    /// Pe denotes Windows Portable Executable files.
+    Pe,
+
+    /// MachO denotes single architecture Mach-O files.
+    MachO,
+
+    /// MachOUniversal denotes multiple architecture (fat) Mach-O files.
+    MachOUniversal,
+
+    /// Wasm denotes WebAssembly modules.
+    Wasm,
+
+    /// Ar denotes static and import libraries.
+    Ar,
+}
+
+impl fmt::Display for ObjectFormat {
+    /// fmt renders an object format.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ObjectFormat::Elf => write!(f, "elf"),
+            ObjectFormat::Pe => write!(f, "pe"),
+            ObjectFormat::MachO => write!(f, "mach-o"),
+            ObjectFormat::MachOUniversal => write!(f, "mach-o-universal"),
+            ObjectFormat::Wasm => write!(f, "wasm"),
+            ObjectFormat::Ar => write!(f, "ar"),
+        }
+    }
+}
+
+/// ObjectHeader models the platform details recorded in an object file header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectHeader {
+    /// format denotes the file format.
+    pub format: ObjectFormat,
+
+    /// arch denotes the rustc arch of the machine type, if known.
+    pub arch: Option<String>,
+
+    /// pointer_width denotes the bitness, if known.
+    pub pointer_width: Option<u32>,
+
+    /// endian denotes the byte order ("little" or "big"), if known.
+    pub endian: Option<String>,
+
+    /// os denotes the rustc os of any ELF OS/ABI marker.
+    pub os: Option<String>,
+}
+
+/// elf_arch resolves the rustc arch of an ELF e_machine value.
+fn elf_arch(machine: u16, pointer_width: u32) -> Option<&'static str> {
+    match (machine, pointer_width) {
+        (2, _) => Some("sparc"),
+        (3, _) => Some("x86"),
+        (4, _) => Some("m68k"),
+        (8, 64) => Some("mips64"),
+        (8, _) => Some("mips"),
+        (20, _) => Some("powerpc"),
+        (21, _) => Some("powerpc64"),
+        (22, _) => Some("s390x"),
+        (40, _) => Some("arm"),
+        (43, _) => Some("sparc64"),
+        (62, _) => Some("x86_64"),
+        (83, _) => Some("avr"),
+        (105, _) => Some("msp430"),
+        (164, _) => Some("hexagon"),
+        (183, _) => Some("aarch64"),
+        (243, 64) => Some("riscv64"),
+        (243, _) => Some("riscv32"),
+        (247, _) => Some("bpf"),
+        (252, _) => Some("csky"),
+        (258, _) => Some("loongarch64"),
+        _ => None,
+    }
+}
+
+/// elf_os resolves the rustc os of an ELF OS/ABI value. The generic System V value names no os.
+fn elf_os(osabi: u8) -> Option<&'static str> {
+    match osabi {
+        2 => Some("netbsd"),
+        3 => Some("linux"),
+        6 => Some("solaris"),
+        9 => Some("freebsd"),
+        12 => Some("openbsd"),
+        _ => None,
+    }
+}
+
+/// pe_arch resolves the rustc arch of a COFF machine value.
+fn pe_arch(machine: u16) -> Option<&'static str> {
+    match machine {
+        0x014c => Some("x86"),
+        0x01c4 => Some("arm"),
+        0x8664 => Some("x86_64"),
+        0xaa64 => Some("aarch64"),
+        _ => None,
+    }
+}
+
+/// macho_arch resolves the rustc arch of a Mach-O cputype.
+fn macho_arch(cputype: u32) -> Option<&'static str> {
+    match cputype & 0x00ff_ffff {
+        7 if cputype == macho::CPU_TYPE_X86_64 => Some("x86_64"),
+        7 => Some("x86"),
+        12 if cputype == 12 => Some("arm"),
+        12 => Some("aarch64"),
+        18 if cputype == 18 => Some("powerpc"),
+        18 => Some("powerpc64"),
+        _ => None,
+    }
+}
+
+/// parse_header reads the platform details of an object file, given its leading bytes.
+pub fn parse_header(data: &[u8]) -> Option<ObjectHeader> {
+    let bytes = |offset: usize, n: usize| -> Option<&[u8]> { data.get(offset..offset + n) };
+    let u16_le = |offset: usize| -> Option<u16> {
+        Some(u16::from_le_bytes(bytes(offset, 2)?.try_into().ok()?))
+    };
+    let u32_le = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(bytes(offset, 4)?.try_into().ok()?))
+    };
+    let u32_be = |offset: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(bytes(offset, 4)?.try_into().ok()?))
+    };
+    let endian = |little: bool| Some(if little { "little" } else { "big" }.to_string());
+
+    if data.starts_with(b"\x7fELF") {
+        let pointer_width: u32 = match *data.get(4)? {
+            1 => 32,
+            2 => 64,
+            _ => return None,
+        };
+        let little_endian: bool = *data.get(5)? == 1;
+        let machine_bytes: [u8; 2] = bytes(18, 2)?.try_into().ok()?;
+        let machine: u16 = if little_endian {
+            u16::from_le_bytes(machine_bytes)
+        } else {
+            u16::from_be_bytes(machine_bytes)
+        };
+
+        return Some(ObjectHeader {
+            format: ObjectFormat::Elf,
+            arch: elf_arch(machine, pointer_width).map(str::to_string),
+            pointer_width: Some(pointer_width),
+            endian: endian(little_endian),
+            os: elf_os(*data.get(7)?).map(str::to_string),
+        });
+    }
+
+    if data.starts_with(b"MZ") {
+        let pe_offset: usize = u32_le(0x3c)? as usize;
+
+        if bytes(pe_offset, 4)? != b"PE\0\0" {
+            return None;
+        }
+
+        let pointer_width: Option<u32> = match u16_le(pe_offset + 24)? {
+            0x010b => Some(32),
+            0x020b => Some(64),
+            _ => None,
+        };
+
+        return Some(ObjectHeader {
+            format: ObjectFormat::Pe,
+            arch: pe_arch(u16_le(pe_offset + 4)?).map(str::to_string),
+            pointer_width,
+            endian: endian(true),
+            os: None,
+        });
+    }
+
+    if u32_be(0)? == macho::FAT_MAGIC {
+        return Some(ObjectHeader {
+            format: ObjectFormat::MachOUniversal,
+            arch: None,
+            pointer_width: None,
+            endian: None,
+            os: None,
+        });
+    }
+
+    if let Some((cputype, _)) = macho::thin_cpu_type(data) {
+        let magic: u32 = u32_le(0)?;
+
+        return Some(ObjectHeader {
+            format: ObjectFormat::MachO,
+            arch: macho_arch(cputype).map(str::to_string),
+            pointer_width: Some(
+                if magic == macho::MH_MAGIC_64 || magic.swap_bytes() == macho::MH_MAGIC_64 {
+                    64
+                } else {
+                    32
+                },
+            ),
+            endian: endian(magic == macho::MH_MAGIC || magic == macho::MH_MAGIC_64),
+            os: None,
+        });
+    }
+
+    if data.starts_with(b"\0asm") {
+        return Some(ObjectHeader {
+            format: ObjectFormat::Wasm,
+            arch: None,
+            pointer_width: None,
+            endian: endian(true),
+            os: None,
+        });
+    }
+
+    if data.starts_with(macho::AR_MAGIC) {
+        return Some(ObjectHeader {
+            format: ObjectFormat::Ar,
+            arch: None,
+            pointer_width: None,
+            endian: None,
+            os: None,
+        });
+    }
+
+    None
+}
+
+/// read_header reads the platform details of an object file, if recognized.
+pub fn read_header(pth: &path::Path) -> Result<Option<ObjectHeader>, CritError> {
+    let mut data: Vec<u8> = Vec::new();
+    fs::File::open(pth)
+        .and_then(|e| e.take(HEADER_LIMIT).read_to_end(&mut data))
+        .map_err(|err| CritError::IOError(format!("unable to read {}: {err}", pth.display())))?;
+    Ok(parse_header(&data))
+}
+
+/// expected_format resolves the executable format of a target.
+pub fn expected_format(metadata: &TargetMetadata) -> ObjectFormat {
+    if metadata.os == "windows" || metadata.os == "uefi" {
+        ObjectFormat::Pe
+    } else if metadata.vendor == "apple" && metadata.arch == "universal2" {
+        ObjectFormat::MachOUniversal
+    } else if metadata.vendor == "apple" {
+        ObjectFormat::MachO
+    } else if metadata.family.iter().any(|e| e == "wasm") || metadata.arch.starts_with("wasm") {
+        ObjectFormat::Wasm
+    } else {
+        ObjectFormat::Elf
+    }
+}
+
+/// arch_matches reports whether a header arch suits a rustc arch.
+fn arch_matches(header_arch: &str, arch: &str) -> bool {
+    match arch {
+        // MIPS revisions and ABIs share one machine type.
+        "mips" | "mips32r6" | "mips64" | "mips64r6" => header_arch.starts_with("mips"),
+        // ARM64EC binaries carry the x86_64 machine type, for compatibility.
+        "arm64ec" => header_arch == "x86_64" || header_arch == "aarch64",
+        _ => header_arch == arch,
+    }
+}
+
+/// check lists the disagreements between an object header and target metadata.
+///
+/// Static and import libraries (ar archives) go unchecked.
+pub fn check(header: &ObjectHeader, metadata: &TargetMetadata) -> Vec<String> {
+    let mut mismatches: Vec<String> = Vec::new();
+
+    if header.format == ObjectFormat::Ar {
+        return mismatches;
+    }
+
+    let format: ObjectFormat = expected_format(metadata);
+
+    if header.format != format {
+        mismatches.push(format!("expected {format}, found {}", header.format));
+    }
+
+    if let Some(arch) = &header.arch
+        && !arch_matches(arch, &metadata.arch)
+    {
+        mismatches.push(format!("expected arch {}, found {arch}", metadata.arch));
+    }
+
+    if let (Some(pointer_width), Ok(expected_pointer_width)) =
+        (header.pointer_width, metadata.pointer_width.parse::<u32>())
+        && pointer_width != expected_pointer_width
+    {
+        mismatches.push(format!(
+            "expected {expected_pointer_width}-bit, found {pointer_width}-bit"
+        ));
+    }
+
+    if let Some(endian) = &header.endian
+        && !metadata.endian.is_empty()
+        && *endian != metadata.endian
+    {
+        mismatches.push(format!(
+            "expected {} endian, found {endian} endian",
+            metadata.endian
+        ));
+    }
+
+    if let Some(os) = &header.os
+        && *os != metadata.os
+        && !(os == "solaris" && metadata.os == "illumos")
+        && !(os == "linux" && metadata.os == "hurd")
+    {
+        mismatches.push(format!("expected os {}, found {os}", metadata.os));
+    }
+
+    mismatches
+}
+
+#[test]
+fn test_check() {
+    let metadata =
+        |arch: &str, vendor: &str, os: &str, pointer_width: &str, endian: &str| TargetMetadata {
+            arch: arch.to_string(),
+            vendor: vendor.to_string(),
+            os: os.to_string(),
+            pointer_width: pointer_width.to_string(),
+            endian: endian.to_string(),
+            ..Default::default()
+        };
+    let linux_aarch64 = metadata("aarch64", "unknown", "linux", "64", "little");
+
+    let mut elf: Vec<u8> = vec![0u8; 64];
+    elf[..4].copy_from_slice(b"\x7fELF");
+    elf[4] = 2;
+    elf[5] = 1;
+    elf[18..20].copy_from_slice(&62u16.to_le_bytes());
+    let header: ObjectHeader = parse_header(&elf).unwrap();
+    assert_eq!(header.format, ObjectFormat::Elf);
+    assert_eq!(header.arch.as_deref(), Some("x86_64"));
+    assert_eq!(
+        check(&header, &linux_aarch64),
+        vec!["expected arch aarch64, found x86_64"]
+    );
+
+    elf[18..20].copy_from_slice(&183u16.to_le_bytes());
+    elf[7] = 9;
+    assert_eq!(
+        check(&parse_header(&elf).unwrap(), &linux_aarch64),
+        vec!["expected os linux, found freebsd"]
+    );
+
+    let mut pe: Vec<u8> = vec![0u8; 256];
+    pe[..2].copy_from_slice(b"MZ");
+    pe[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+    pe[0x80..0x84].copy_from_slice(b"PE\0\0");
+    pe[0x84..0x86].copy_from_slice(&0x014cu16.to_le_bytes());
+    pe[0x98..0x9a].copy_from_slice(&0x010bu16.to_le_bytes());
+    let header: ObjectHeader = parse_header(&pe).unwrap();
+    assert_eq!(header.format, ObjectFormat::Pe);
+    assert!(check(&header, &metadata("x86", "pc", "windows", "32", "little")).is_empty());
+    assert_eq!(
+        check(&header, &linux_aarch64),
+        vec![
+            "expected elf, found pe",
+            "expected arch aarch64, found x86",
+            "expected 64-bit, found 32-bit",
+        ]
+    );
+
+    let mut macho: Vec<u8> = Vec::new();
+    macho.extend(macho::MH_MAGIC_64.to_le_bytes());
+    macho.extend(macho::CPU_TYPE_ARM64.to_le_bytes());
+    macho.extend(0u32.to_le_bytes());
+    let header: ObjectHeader = parse_header(&macho).unwrap();
+    assert_eq!(header.format, ObjectFormat::MachO);
+    assert!(
+        check(
+            &header,
+            &metadata("aarch64", "apple", "macos", "64", "little")
+        )
+        .is_empty()
+    );
+
+    let fat: Vec<u8> = macho::render_fat(&[macho::Slice {
+        cputype: macho::CPU_TYPE_ARM64,
+        cpusubtype: 0,
+        data: macho.clone(),
+    }])
+    .unwrap();
+    let header: ObjectHeader = parse_header(&fat).unwrap();
+    assert_eq!(header.format, ObjectFormat::MachOUniversal);
+    assert!(
+        check(
+            &header,
+            &metadata("universal2", "apple", "macos", "64", "little")
+        )
+        .is_empty()
+    );
+    assert_eq!(
+        check(
+            &header,
+            &metadata("aarch64", "apple", "macos", "64", "little")
+        ),
+        vec!["expected mach-o, found mach-o-universal"]
+    );
+
+    let wasm: Vec<u8> = b"\0asm\x01\0\0\0".to_vec();
+    assert_eq!(parse_header(&wasm).unwrap().format, ObjectFormat::Wasm);
+    assert_eq!(parse_header(b"!<arch>\n").unwrap().format, ObjectFormat::Ar);
+    assert_eq!(parse_header(b"console.log(1);"), None);
+}
+
+/// verify checks an artifact header against target metadata.
+///
+/// Unrecognized files, such as emscripten JavaScript loaders, pass.
+pub fn verify(pth: &path::Path, metadata: &TargetMetadata) -> Result<(), CritError> {
+    let header: ObjectHeader = match read_header(pth)? {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+    let mismatches: Vec<String> = check(&header, metadata);
+
+    if !mismatches.is_empty() {
+        return Err(CritError::IOError(format!(
+            "artifact mismatch: {}: {}",
+            pth.display(),
+            mismatches.join(", ")
+        )));
+    }
+
+    Ok(())
+}